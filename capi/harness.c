@@ -0,0 +1,107 @@
+/* C harness of the capi feature: make -C capi test */
+
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+#include "libtzfile.h"
+
+#define ZONEINFO "/usr/share/zoneinfo/"
+
+static int failures = 0;
+
+#define CHECK(cond)                                                           \
+    do {                                                                      \
+        if (!(cond)) {                                                        \
+            fprintf(stderr, "%s:%d: check failed: %s\n", __FILE__, __LINE__, \
+                    #cond);                                                   \
+            failures++;                                                       \
+        }                                                                     \
+    } while (0)
+
+static unsigned char *read_file(const char *path, size_t *len) {
+    FILE *f = fopen(path, "rb");
+    unsigned char *buf;
+    long size;
+    if (f == NULL) return NULL;
+    fseek(f, 0, SEEK_END);
+    size = ftell(f);
+    rewind(f);
+    buf = malloc(size);
+    if (buf != NULL && fread(buf, 1, size, f) != (size_t)size) {
+        free(buf);
+        buf = NULL;
+    }
+    fclose(f);
+    *len = size;
+    return buf;
+}
+
+int main(void) {
+    Tz *paris = NULL, *tokyo = NULL, *invalid = NULL;
+    TzLocalTimeType ltt;
+    int64_t t;
+    size_t len;
+    unsigned char *buf;
+
+    /* Loading */
+    CHECK(tz_from_path(ZONEINFO "Europe/Paris", &paris) == TZ_OK);
+    CHECK(tz_from_path(ZONEINFO "Nowhere/Special", &invalid) ==
+          TZ_ERROR_INVALID_TIMEZONE);
+    CHECK(invalid == NULL);
+    buf = read_file(ZONEINFO "Asia/Tokyo", &len);
+    CHECK(buf != NULL);
+    CHECK(tz_from_bytes(buf, len, "Asia/Tokyo", &tokyo) == TZ_OK);
+    CHECK(tz_from_bytes(buf, 10, "Asia/Tokyo", &invalid) != TZ_OK);
+    free(buf);
+    CHECK(tz_from_path(NULL, &invalid) == TZ_ERROR_INVALID_ARGUMENT);
+    if (paris == NULL || tokyo == NULL) {
+        fprintf(stderr, "zones not loaded\n");
+        return 1;
+    }
+
+    /* Lookup: 2025-07-01T12:00:00Z */
+    CHECK(tz_lookup(paris, 1751371200, &ltt) == TZ_OK);
+    CHECK(ltt.utc_offset == 7200 && ltt.isdst);
+    CHECK(strcmp(ltt.abbreviation, "CEST") == 0);
+    CHECK(tz_lookup(tokyo, 1751371200, &ltt) == TZ_OK);
+    CHECK(ltt.utc_offset == 32400 && !ltt.isdst);
+    CHECK(strcmp(ltt.abbreviation, "JST") == 0);
+    CHECK(tz_lookup(paris, 1751371200, NULL) == TZ_ERROR_INVALID_ARGUMENT);
+
+    /* Conversions */
+    CHECK(tz_utc_to_local(paris, 1751371200, &t) == TZ_OK);
+    CHECK(t == 1751378400);
+    /* 2025-03-30T02:30 is skipped in Paris */
+    CHECK(tz_local_to_utc(paris, 1743301800, TZ_DISAMBIGUATION_COMPATIBLE,
+                          &t) == TZ_OK);
+    CHECK(t == 1743298200);
+    CHECK(tz_local_to_utc(paris, 1743301800, TZ_DISAMBIGUATION_REJECT, &t) ==
+          TZ_ERROR_SKIPPED_LOCAL_TIME);
+    /* 2025-10-26T02:30 occurs twice in Paris */
+    CHECK(tz_local_to_utc(paris, 1761445800, TZ_DISAMBIGUATION_EARLIER, &t) ==
+          TZ_OK);
+    CHECK(t == 1761438600);
+    CHECK(tz_local_to_utc(paris, 1761445800, TZ_DISAMBIGUATION_LATER, &t) ==
+          TZ_OK);
+    CHECK(t == 1761442200);
+    CHECK(tz_local_to_utc(paris, 1761445800, 42, &t) ==
+          TZ_ERROR_INVALID_ARGUMENT);
+
+    /* Messages */
+    CHECK(strcmp(tz_strerror(TZ_ERROR_AMBIGUOUS_LOCAL_TIME),
+                 "Ambiguous local time") == 0);
+    CHECK(strcmp(tz_strerror(TZ_ERROR_INTERNAL), "Internal error") == 0);
+    CHECK(strcmp(tz_strerror(-1), "Unknown error code") == 0);
+
+    tz_free(paris);
+    tz_free(tokyo);
+    tz_free(NULL);
+
+    if (failures > 0) {
+        fprintf(stderr, "%d check(s) failed\n", failures);
+        return 1;
+    }
+    printf("capi harness: all checks passed\n");
+    return 0;
+}
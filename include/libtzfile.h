@@ -0,0 +1,216 @@
+/* Generated by cbindgen from src/capi.rs: do not edit */
+
+#ifndef LIBTZFILE_H
+#define LIBTZFILE_H
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * Success
+ */
+#define TZ_OK 0
+
+/**
+ * Invalid timezone
+ */
+#define TZ_ERROR_INVALID_TIMEZONE 1
+
+/**
+ * Invalid TZfile
+ */
+#define TZ_ERROR_INVALID_MAGIC 2
+
+/**
+ * Bad utf8 string
+ */
+#define TZ_ERROR_BAD_UTF8_STRING 3
+
+/**
+ * Only V2 (and later) format is supported
+ */
+#define TZ_ERROR_UNSUPPORTED_FORMAT 4
+
+/**
+ * No data matched the request
+ */
+#define TZ_ERROR_NO_DATA 5
+
+/**
+ * Parsing error
+ */
+#define TZ_ERROR_PARSE 6
+
+/**
+ * Empty string
+ */
+#define TZ_ERROR_EMPTY_STRING 7
+
+/**
+ * Could not convert to json
+ */
+#define TZ_ERROR_JSON 8
+
+/**
+ * Invalid format string
+ */
+#define TZ_ERROR_INVALID_FORMAT 9
+
+/**
+ * Ambiguous local time
+ */
+#define TZ_ERROR_AMBIGUOUS_LOCAL_TIME 10
+
+/**
+ * Local time skipped by a transition
+ */
+#define TZ_ERROR_SKIPPED_LOCAL_TIME 11
+
+/**
+ * Ambiguous timezone abbreviation
+ */
+#define TZ_ERROR_AMBIGUOUS_ABBREVIATION 12
+
+/**
+ * Offset inconsistent with the timezone
+ */
+#define TZ_ERROR_INCONSISTENT_OFFSET 13
+
+/**
+ * Unsupported critical annotation
+ */
+#define TZ_ERROR_UNSUPPORTED_ANNOTATION 14
+
+/**
+ * Null pointer or invalid argument
+ */
+#define TZ_ERROR_INVALID_ARGUMENT 15
+
+/**
+ * Internal error (a bug of the library)
+ */
+#define TZ_ERROR_INTERNAL 16
+
+/**
+ * Earlier instant for ambiguous local times, later instant for skipped local times
+ */
+#define TZ_DISAMBIGUATION_COMPATIBLE 0
+
+/**
+ * Earlier instant
+ */
+#define TZ_DISAMBIGUATION_EARLIER 1
+
+/**
+ * Later instant
+ */
+#define TZ_DISAMBIGUATION_LATER 2
+
+/**
+ * Ambiguous or skipped local times are errors
+ */
+#define TZ_DISAMBIGUATION_REJECT 3
+
+/**
+ * Size of the abbreviation buffer of TzLocalTimeType, terminating NUL included
+ */
+#define TZ_ABBREVIATION_SIZE 16
+
+/**
+ * This is the crate's primary structure, which contains the TZfile fields.
+ * With the json feature enabled, it implements the Serialize and Deserialize traits.
+ */
+typedef struct Tz Tz;
+
+/**
+ * Local time type in effect at an instant, as written by ```tz_lookup```.
+ */
+typedef struct TzLocalTimeType {
+  /**
+   * Offset to UTC, in seconds
+   */
+  int32_t utc_offset;
+  /**
+   * Is DST observed ?
+   */
+  bool isdst;
+  /**
+   * TZ abbreviation, NUL terminated
+   */
+  char abbreviation[TZ_ABBREVIATION_SIZE];
+} TzLocalTimeType;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Returns the static, NUL terminated message of an error code.
+ */
+const char *tz_strerror(int code);
+
+/**
+ * Parses a TZfile from its content (len bytes at buf). The name is the zone's name (ie. Europe/Paris).
+ * On success, the handle written to out must be released with ```tz_free```.
+ *
+ * # Safety
+ * buf must point to len readable bytes, name must be a NUL terminated string, and out must be writable.
+ */
+int tz_from_bytes(const uint8_t *buf,
+                  size_t len,
+                  const char *name,
+                  struct Tz **out);
+
+/**
+ * Reads and parses a TZfile (ie. /usr/share/zoneinfo/Europe/Paris).
+ * On success, the handle written to out must be released with ```tz_free```.
+ *
+ * # Safety
+ * path must be a NUL terminated string, and out must be writable.
+ */
+int tz_from_path(const char *path, struct Tz **out);
+
+/**
+ * Releases a handle. Null handles are ignored.
+ *
+ * # Safety
+ * tz must be null or a handle returned by ```tz_from_bytes``` or ```tz_from_path```, not released yet.
+ */
+void tz_free(struct Tz *tz);
+
+/**
+ * Writes the local time type (offset to UTC, DST and abbreviation) in effect at an instant.
+ *
+ * # Safety
+ * tz must be a live handle, and out must be writable.
+ */
+int tz_lookup(const struct Tz *tz, int64_t utc, struct TzLocalTimeType *out);
+
+/**
+ * Converts an instant to the local time.
+ *
+ * # Safety
+ * tz must be a live handle, and out must be writable.
+ */
+int tz_utc_to_local(const struct Tz *tz, int64_t utc, int64_t *out);
+
+/**
+ * Converts a local time to an instant. Ambiguous and skipped local times are handled according to the disambiguation
+ * policy (one of the TZ_DISAMBIGUATION_ constants).
+ *
+ * # Safety
+ * tz must be a live handle, and out must be writable.
+ */
+int tz_local_to_utc(const struct Tz *tz,
+                    int64_t local,
+                    int disambiguation,
+                    int64_t *out);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* LIBTZFILE_H */
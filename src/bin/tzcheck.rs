@@ -0,0 +1,355 @@
+//! zoneinfo directory validator (built with the parse feature).
+//!
+//! ```text
+//! tzcheck [zoneinfo_directory]
+//! ```
+//!
+//! Every TZfile of the directory (TZDIR or ```/usr/share/zoneinfo``` by default) is parsed, and checked for semantic
+//! violations, disagreements between its version 1 and version 2 data, and a TZ string footer which does not match the
+//! end of its transition times table. Broken symbolic links are reported, and format versions differing across files
+//! are noted.
+//! The exit status is 1 if a problem was found.
+
+use chrono::{Datelike, TimeZone, Utc};
+use libtzfile::Tz;
+use std::{
+    collections::BTreeMap,
+    env, fs,
+    path::{Path, PathBuf},
+    process,
+};
+
+// TZif magic four bytes
+const MAGIC: &[u8] = b"TZif";
+// Header length
+const HEADER_LEN: usize = 44;
+// Offset to UTC range recommended by RFC 8536
+const UTOFF_RANGE: std::ops::RangeInclusive<isize> = -89999..=93599;
+
+// Local time type: offset to UTC, DST flag, abbreviation
+type LocalType = (isize, bool, String);
+
+// Version 1 data: transition times and the local time types following them, and local time types
+struct V1Data {
+    times: Vec<(i64, LocalType)>,
+    typecnt: usize,
+}
+
+fn main() {
+    let root = match env::args().nth(1) {
+        Some(arg) if arg == "--help" => {
+            println!("usage: tzcheck [zoneinfo_directory]");
+            return;
+        }
+        Some(arg) => PathBuf::from(arg),
+        None => PathBuf::from(env::var("TZDIR").unwrap_or(String::from("/usr/share/zoneinfo"))),
+    };
+    let mut files = Vec::new();
+    if let Err(e) = walk(&root, &mut files) {
+        eprintln!("tzcheck: {}: {}", root.display(), e);
+        process::exit(2);
+    }
+    files.sort();
+
+    let mut problems = 0;
+    let mut report = |path: &Path, message: String| {
+        let name = path.strip_prefix(&root).unwrap_or(path);
+        println!("{}: {}", name.display(), message);
+        problems += 1;
+    };
+    let mut checked = 0;
+    let mut versions: BTreeMap<u8, Vec<PathBuf>> = BTreeMap::new();
+    for path in &files {
+        if fs::symlink_metadata(path).is_ok_and(|m| m.file_type().is_symlink())
+            && fs::metadata(path).is_err()
+        {
+            let target = fs::read_link(path).unwrap_or_default();
+            report(
+                path,
+                format!("broken symbolic link to {}", target.display()),
+            );
+            continue;
+        }
+        let buf = match fs::read(path) {
+            Ok(buf) => buf,
+            Err(e) => {
+                report(path, format!("unreadable: {}", e));
+                continue;
+            }
+        };
+        // Other files of the directory (ie. zone.tab) are not TZfiles
+        if !buf.starts_with(MAGIC) {
+            continue;
+        }
+        checked += 1;
+        let name = path.strip_prefix(&root).unwrap_or(path).to_string_lossy();
+        let tz = match Tz::from_bytes(&buf, &name) {
+            Ok(tz) => tz,
+            Err(e) => {
+                report(path, format!("parse failure: {}", e));
+                continue;
+            }
+        };
+        versions
+            .entry(tz.tzh_version)
+            .or_default()
+            .push(path.clone());
+        for message in semantics(&tz)
+            .into_iter()
+            .chain(v1_disagreements(&buf, &tz))
+            .chain(footer_mismatch(&tz))
+        {
+            report(path, message);
+        }
+    }
+
+    if versions.len() > 1 {
+        let skew: Vec<String> = versions
+            .iter()
+            .map(|(version, paths)| {
+                let example = paths[0].strip_prefix(&root).unwrap_or(&paths[0]);
+                format!(
+                    "{} in version {} (ie. {})",
+                    paths.len(),
+                    version,
+                    example.display()
+                )
+            })
+            .collect();
+        // zic writes the lowest version the data needs: only a note
+        println!("note: versions differ across files: {}", skew.join(", "));
+    }
+    println!("{} TZfiles checked, {} problem(s)", checked, problems);
+    if problems > 0 {
+        process::exit(1);
+    }
+}
+
+// Files and symbolic links of the directory, recursively
+fn walk(dir: &Path, files: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            walk(&path, files)?;
+        } else if file_type.is_symlink() && path.is_dir() {
+            // Links to directories (ie. posix -> .) would be walked twice
+            continue;
+        } else {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+fn local_type(tz: &Tz, i: usize) -> LocalType {
+    let tti = &tz.tzh_typecnt[i];
+    (
+        tti.tt_utoff,
+        tti.tt_isdst == 1,
+        tz.tz_abbr[tti.tt_abbrind as usize].clone(),
+    )
+}
+
+// Local time type after the recorded transition time t
+fn recorded_type(tz: &Tz, t: i64) -> Option<LocalType> {
+    let i = tz.tzh_timecnt_data.iter().position(|&r| r == t)?;
+    Some(local_type(tz, tz.tzh_timecnt_indices[i] as usize))
+}
+
+// RFC 8536 constraints on the version 2 data
+fn semantics(tz: &Tz) -> Vec<String> {
+    let mut messages = Vec::new();
+    if let Some(w) = tz.tzh_timecnt_data.windows(2).find(|w| w[0] >= w[1]) {
+        messages.push(format!(
+            "transition times not in ascending order ({} then {})",
+            w[0], w[1]
+        ));
+    }
+    for (i, tti) in tz.tzh_typecnt.iter().enumerate() {
+        if tti.tt_isdst > 1 {
+            messages.push(format!(
+                "local time type {}: invalid isdst {}",
+                i, tti.tt_isdst
+            ));
+        }
+        if !UTOFF_RANGE.contains(&tti.tt_utoff) {
+            messages.push(format!(
+                "local time type {}: offset {} out of range",
+                i, tti.tt_utoff
+            ));
+        }
+    }
+    for abbr in &tz.tz_abbr {
+        if abbr.len() < 3
+            || !abbr
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '+' || c == '-')
+        {
+            messages.push(format!("invalid abbreviation \"{}\"", abbr));
+        }
+    }
+    // Rule times out of 0..=24 hours are an extension of version 3
+    if let Some(dst) = tz.tz_footer.as_ref().and_then(|f| f.dst.as_ref()) {
+        let hours = 0..=86400;
+        if tz.tzh_version < 3 && !(hours.contains(&dst.start_time) && hours.contains(&dst.end_time))
+        {
+            messages.push(format!(
+                "version {} with a TZ string footer needing version 3",
+                tz.tzh_version
+            ));
+        }
+    }
+    let leaps = &tz.tzh_leapcnt_data;
+    if let Some(w) = leaps
+        .windows(2)
+        .find(|w| w[1].ls_trans - w[0].ls_trans < 2419199)
+    {
+        messages.push(format!(
+            "leap second records too close ({} then {})",
+            w[0].ls_trans, w[1].ls_trans
+        ));
+    }
+    if let Some(w) = leaps
+        .windows(2)
+        .find(|w| (w[1].ls_corr - w[0].ls_corr).abs() != 1)
+    {
+        messages.push(format!(
+            "leap second correction changing by more than one ({} then {})",
+            w[0].ls_corr, w[1].ls_corr
+        ));
+    }
+    messages
+}
+
+fn v1_data(buf: &[u8]) -> Option<V1Data> {
+    let count = |i: usize| {
+        let start = 20 + 4 * i;
+        Some(u32::from_be_bytes(buf.get(start..start + 4)?.try_into().ok()?) as usize)
+    };
+    let (timecnt, typecnt, charcnt) = (count(3)?, count(4)?, count(5)?);
+    let times_end = HEADER_LEN + 4 * timecnt;
+    let indices_end = times_end + timecnt;
+    let types_end = indices_end + 6 * typecnt;
+    let chars = buf.get(types_end..types_end + charcnt)?;
+    let abbr = |i: usize| {
+        let abbr = chars.get(i..)?;
+        let end = abbr.iter().position(|&c| c == 0)?;
+        Some(String::from_utf8_lossy(&abbr[..end]).to_string())
+    };
+    let mut types = Vec::new();
+    for tti in buf.get(indices_end..types_end)?.chunks_exact(6) {
+        types.push((
+            i32::from_be_bytes(tti[0..4].try_into().ok()?) as isize,
+            tti[4] == 1,
+            abbr(tti[5] as usize)?,
+        ));
+    }
+    let mut times = Vec::new();
+    for (t, &i) in buf
+        .get(HEADER_LEN..times_end)?
+        .chunks_exact(4)
+        .zip(buf.get(times_end..indices_end)?)
+    {
+        let t = i32::from_be_bytes(t.try_into().ok()?) as i64;
+        times.push((t, types.get(i as usize)?.clone()));
+    }
+    Some(V1Data { times, typecnt })
+}
+
+// The version 1 data must be the version 2 data restricted to 32 bits transition times
+fn v1_disagreements(buf: &[u8], tz: &Tz) -> Vec<String> {
+    let v1 = match v1_data(buf) {
+        Some(v1) => v1,
+        None => return vec![String::from("truncated version 1 data")],
+    };
+    let mut messages = Vec::new();
+    if v1_len(buf).is_none_or(|len| buf.get(4) != buf.get(len + 4)) {
+        messages.push(String::from(
+            "version 1 and version 2 headers with different versions",
+        ));
+    }
+    // Data written by zic -b slim: nothing to compare
+    if v1.times.is_empty() && v1.typecnt <= 1 {
+        return messages;
+    }
+    for (t, v1_type) in &v1.times {
+        // The first 32 bits transition time may stand for earlier ones
+        if *t == i32::MIN as i64 {
+            continue;
+        }
+        match recorded_type(tz, *t) {
+            Some(v2_type) if v2_type == *v1_type => (),
+            Some(v2_type) => messages.push(format!(
+                "version 1 and version 2 local time types differ at {} ({:?} and {:?})",
+                t, v1_type, v2_type
+            )),
+            None => messages.push(format!(
+                "version 1 transition time {} missing from version 2",
+                t
+            )),
+        }
+    }
+    let v1_times: Vec<i64> = v1.times.iter().map(|(t, _)| *t).collect();
+    let range = (i32::MIN as i64 + 1)..=(i32::MAX as i64);
+    for t in tz.tzh_timecnt_data.iter().filter(|t| range.contains(t)) {
+        if !v1_times.contains(t) {
+            messages.push(format!(
+                "version 2 transition time {} missing from version 1",
+                t
+            ));
+        }
+    }
+    messages
+}
+
+// Length of the version 1 header and data, which the version 2 header follows
+fn v1_len(buf: &[u8]) -> Option<usize> {
+    let count = |i: usize| {
+        let start = 20 + 4 * i;
+        Some(u32::from_be_bytes(buf.get(start..start + 4)?.try_into().ok()?) as usize)
+    };
+    Some(
+        HEADER_LEN
+            + 5 * count(3)?
+            + 6 * count(4)?
+            + count(5)?
+            + 8 * count(2)?
+            + count(1)?
+            + count(0)?,
+    )
+}
+
+// After the last transition time, the footer must give the local time type of the table, and the footer's
+// transitions of that year must be in the table
+fn footer_mismatch(tz: &Tz) -> Vec<String> {
+    let (footer, &last) = match (&tz.tz_footer, tz.tzh_timecnt_data.last()) {
+        (Some(footer), Some(last)) => (footer, last),
+        _ => return Vec::new(),
+    };
+    let mut messages = Vec::new();
+    let (utoff, isdst, abbr) = footer.ttinfo_at(last);
+    let footer_type = (utoff, isdst, abbr.to_string());
+    if let Some(table_type) = recorded_type(tz, last) {
+        if table_type != footer_type {
+            messages.push(format!(
+                "TZ string footer \"{}\" gives {:?} after the last transition time {}, the table {:?}",
+                footer.raw, footer_type, last, table_type
+            ));
+        }
+    }
+    let year = match Utc.timestamp_opt(last, 0).single() {
+        Some(t) => t.year(),
+        None => return messages,
+    };
+    for (t, _) in footer.transitions(year).into_iter().flatten() {
+        if t <= last && !tz.tzh_timecnt_data.contains(&t) {
+            messages.push(format!(
+                "TZ string footer \"{}\" transition time {} missing from the table",
+                footer.raw, t
+            ));
+        }
+    }
+    messages
+}
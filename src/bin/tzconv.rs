@@ -0,0 +1,114 @@
+//! Time converter between zones (built with the parse feature).
+//!
+//! ```text
+//! tzconv [--earlier|--later|--reject] "2025-03-30 02:30" Europe/Paris America/New_York Asia/Tokyo
+//! ```
+//!
+//! The source zone is a name read from the TZDIR directory (```/usr/share/zoneinfo``` by default) or a TZ abbreviation
+//! (ie. CET). Local times skipped or repeated in the source zone are reported, and resolved according to the option
+//! (the later instant for a skipped time and the earlier one for a repeated time by default).
+
+use chrono::{DateTime, FixedOffset};
+use libtzfile::{Disambiguation, TzError, ZoneLoader, ZonedDateTime};
+use std::{env, process};
+
+const USAGE: &str = "usage: tzconv [--earlier|--later|--reject] time source_zone target_zone...
+  --earlier  Earlier instant for skipped or repeated local times
+  --later    Later instant for skipped or repeated local times
+  --reject   Skipped or repeated local times are errors";
+
+fn main() {
+    let mut disambiguation = Disambiguation::Compatible;
+    let mut args = Vec::new();
+    for arg in env::args().skip(1) {
+        match arg.as_str() {
+            "--earlier" => disambiguation = Disambiguation::Earlier,
+            "--later" => disambiguation = Disambiguation::Later,
+            "--reject" => disambiguation = Disambiguation::Reject,
+            "--help" => {
+                println!("{}", USAGE);
+                process::exit(0);
+            }
+            _ if arg.starts_with("--") => fail(&format!("unknown option: {}\n{}", arg, USAGE), 2),
+            _ => args.push(arg),
+        }
+    }
+    let (time, source, targets) = match &args[..] {
+        [time, source, targets @ ..] if !targets.is_empty() => (time, source, targets),
+        _ => fail(USAGE, 2),
+    };
+
+    let loader = ZoneLoader::new(env::var("TZDIR").unwrap_or(String::from("/usr/share/zoneinfo")));
+    let input = format!("{} {}", time, source);
+    let parse = |disambiguation| loader.parse_datetime(&input, disambiguation, &[]);
+    let parsed = match parse(Disambiguation::Reject) {
+        Ok(parsed) => parsed,
+        Err(e @ (TzError::AmbiguousLocalTime | TzError::SkippedLocalTime)) => {
+            if disambiguation == Disambiguation::Reject {
+                fail(&format!("{}: {}", input, e), 1);
+            }
+            let parsed = parse(disambiguation).unwrap_or_else(|e| fail(&e.to_string(), 1));
+            let used = local(&parsed.local, &parsed.abbreviation);
+            if e == TzError::AmbiguousLocalTime {
+                let earlier =
+                    parse(Disambiguation::Earlier).unwrap_or_else(|e| fail(&e.to_string(), 1));
+                let later =
+                    parse(Disambiguation::Later).unwrap_or_else(|e| fail(&e.to_string(), 1));
+                eprintln!(
+                    "tzconv: warning: {} occurs twice in {} ({} then {}), using {}",
+                    time, source, earlier.abbreviation, later.abbreviation, used
+                );
+            } else {
+                eprintln!(
+                    "tzconv: warning: {} is skipped in {}, using {}",
+                    time, source, used
+                );
+            }
+            parsed
+        }
+        Err(e) => fail(&format!("{}: {}", input, e), 1),
+    };
+
+    let width = targets
+        .iter()
+        .chain([source])
+        .map(|z| z.len())
+        .max()
+        .unwrap_or(0)
+        + 2;
+    print(width, source, &parsed);
+    for target in targets {
+        let tz = loader
+            .load(target)
+            .unwrap_or_else(|e| fail(&format!("{}: {}", target, e), 1));
+        let ltt = tz.lookup(parsed.utc);
+        let offset = FixedOffset::east_opt(ltt.utc_offset as i32)
+            .unwrap_or_else(|| fail(&format!("{}: offset to UTC out of range", target), 1));
+        let converted = ZonedDateTime {
+            utc: parsed.utc,
+            local: parsed.utc.with_timezone(&offset),
+            zone: Some(target.clone()),
+            abbreviation: ltt.abbreviation,
+        };
+        print(width, target, &converted);
+    }
+}
+
+fn print(width: usize, zone: &str, zoned: &ZonedDateTime) {
+    println!(
+        "{:width$}{} ({})",
+        zone,
+        local(&zoned.local, &zoned.abbreviation),
+        zoned.local.offset(),
+        width = width
+    );
+}
+
+fn local(local: &DateTime<FixedOffset>, abbreviation: &str) -> String {
+    format!("{} {}", local.format("%Y-%m-%d %H:%M:%S"), abbreviation)
+}
+
+fn fail(message: &str, code: i32) -> ! {
+    eprintln!("tzconv: {}", message);
+    process::exit(code)
+}
@@ -0,0 +1,191 @@
+//! zdump equivalent (built with the json feature): prints the current time in zones, or their transitions with -v.
+//!
+//! ```text
+//! tzdump [-v] [-c [lo,]hi] [--footer] [--json] zone...
+//! ```
+//!
+//! Zones are names read from the TZDIR directory (```/usr/share/zoneinfo``` by default), or paths to TZfiles.
+
+use chrono::{DateTime, TimeDelta, TimeZone, Utc};
+use libtzfile::{Transition, Tz, TzError, ZoneLoader};
+use serde::Serialize;
+use std::{env, process};
+
+// Default cut-off years, as zdump's
+const LO_YEAR: i32 = -500;
+const HI_YEAR: i32 = 2500;
+
+const USAGE: &str = "usage: tzdump [-v] [-c [lo,]hi] [--footer] [--json] zone...
+  -v          List transitions verbosely, as zdump -v
+  -c [lo,]hi  Start at year lo (default -500), end before year hi (default 2500)
+  --footer    Only list the transitions generated from the TZ string footer
+  --json      Output JSON";
+
+#[derive(Debug, Default)]
+struct Options {
+    verbose: bool,
+    lo: Option<i32>,
+    hi: Option<i32>,
+    footer: bool,
+    json: bool,
+    zones: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct ZoneDump<'a> {
+    timezone: &'a str,
+    transitions: Vec<Transition>,
+}
+
+fn main() {
+    let options = match options(env::args().skip(1)) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("tzdump: {}\n{}", e, USAGE);
+            process::exit(2);
+        }
+    };
+    let loader = ZoneLoader::new(env::var("TZDIR").unwrap_or(String::from("/usr/share/zoneinfo")));
+    let mut zones = Vec::new();
+    for name in &options.zones {
+        match load(&loader, name) {
+            Ok(tz) => zones.push((name.as_str(), tz)),
+            Err(e) => {
+                eprintln!("tzdump: {}: {}", name, e);
+                process::exit(1);
+            }
+        }
+    }
+
+    if options.json {
+        let dumps: Vec<ZoneDump> = zones
+            .iter()
+            .map(|(name, tz)| ZoneDump {
+                timezone: name,
+                transitions: transitions(tz, &options),
+            })
+            .collect();
+        match serde_json::to_string(&dumps) {
+            Ok(json) => println!("{}", json),
+            Err(e) => {
+                eprintln!("tzdump: {}", e);
+                process::exit(1);
+            }
+        }
+    } else if options.verbose {
+        for (name, tz) in &zones {
+            verbose(name, tz, &options);
+        }
+    } else {
+        let width = options.zones.iter().map(|z| z.len()).max().unwrap_or(0) + 2;
+        let now = Utc::now();
+        for (name, tz) in &zones {
+            println!("{:width$}{}", name, local(tz, now), width = width);
+        }
+    }
+}
+
+fn options(mut args: impl Iterator<Item = String>) -> Result<Options, String> {
+    let mut options = Options::default();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-v" => options.verbose = true,
+            "--footer" => options.footer = true,
+            "--json" => options.json = true,
+            "--help" => {
+                println!("{}", USAGE);
+                process::exit(0);
+            }
+            "-c" => {
+                let cutoff = args.next().ok_or("-c needs a value")?;
+                let year = |y: &str| y.parse::<i32>().map_err(|_| format!("invalid year: {}", y));
+                match cutoff.split_once(',') {
+                    Some((lo, hi)) => {
+                        options.lo = Some(year(lo)?);
+                        options.hi = Some(year(hi)?);
+                    }
+                    None => options.hi = Some(year(&cutoff)?),
+                }
+            }
+            _ if arg.starts_with('-') && arg.len() > 1 => {
+                return Err(format!("unknown option: {}", arg))
+            }
+            _ => options.zones.push(arg),
+        }
+    }
+    if options.zones.is_empty() {
+        return Err(String::from("no zone given"));
+    }
+    Ok(options)
+}
+
+fn load(loader: &ZoneLoader, name: &str) -> Result<Tz, TzError> {
+    if std::path::Path::new(name).is_absolute() {
+        Tz::new(name)
+    } else {
+        loader.load(name)
+    }
+}
+
+// Start of a year, clamped to chrono's range
+fn year_start(year: i32) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(year, 1, 1, 0, 0, 0)
+        .single()
+        .unwrap_or(if year < 0 {
+            DateTime::<Utc>::MIN_UTC
+        } else {
+            DateTime::<Utc>::MAX_UTC
+        })
+}
+
+// Transitions within the cut-off years which change the local time type
+fn transitions(tz: &Tz, options: &Options) -> Vec<Transition> {
+    let lo = year_start(options.lo.unwrap_or(LO_YEAR));
+    let hi = year_start(options.hi.unwrap_or(HI_YEAR));
+    let recorded_end = tz.tzh_timecnt_data.last().copied();
+    tz.transitions_from(lo)
+        .take_while(|tt| tt.time < hi)
+        .filter(|tt| !options.footer || recorded_end.is_none_or(|r| tt.time.timestamp() > r))
+        .filter(|tt| {
+            let before = tz.lookup(tt.time - chrono::Duration::seconds(1));
+            (
+                before.utc_offset,
+                before.isdst,
+                before.abbreviation.as_str(),
+            ) != (tt.new_utc_offset, tt.isdst, tt.abbreviation.as_str())
+        })
+        .collect()
+}
+
+fn verbose(name: &str, tz: &Tz, options: &Options) {
+    // Instants out of the localtime range, as printed by zdump
+    println!("{}  {} = NULL", name, i64::MIN);
+    println!("{}  {} = NULL", name, i64::MIN + 86400);
+    for tt in transitions(tz, options) {
+        for instant in [tt.time - chrono::Duration::seconds(1), tt.time] {
+            let ltt = tz.lookup(instant);
+            println!(
+                "{}  {} UT = {} isdst={} gmtoff={}",
+                name,
+                instant.format("%a %b %e %H:%M:%S %Y"),
+                local(tz, instant),
+                i32::from(ltt.isdst),
+                ltt.utc_offset
+            );
+        }
+    }
+    println!("{}  {} = NULL", name, i64::MAX - 86400);
+    println!("{}  {} = NULL", name, i64::MAX);
+}
+
+// Local time and abbreviation, as printed by zdump
+fn local(tz: &Tz, instant: DateTime<Utc>) -> String {
+    let ltt = tz.lookup(instant);
+    // TZfile offsets may exceed the range of FixedOffset (one day)
+    let local = instant.naive_utc() + TimeDelta::seconds(ltt.utc_offset as i64);
+    format!(
+        "{} {}",
+        local.format("%a %b %e %H:%M:%S %Y"),
+        ltt.abbreviation
+    )
+}
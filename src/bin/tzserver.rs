@@ -0,0 +1,231 @@
+//! HTTP time API server compatible with worldtimeapi.org (built with the json feature).
+//!
+//! ```text
+//! tzserver [--listen address:port]
+//! ```
+//!
+//! Zones are read from the TZDIR directory (```/usr/share/zoneinfo``` by default). Routes:
+//! - ```/api/timezone``` : the list of zones, ```/api/timezone/{area}``` : the zones of an area
+//! - ```/api/timezone/{area}/{location}[/{region}]``` : the current time in a zone, in the worldtimeapi.org schema
+//!   (```Tzinfo::worldtimeapi```)
+//!
+//! With a ```.txt``` suffix, the responses are plain text (one zone, or one ```key: value``` field, per line).
+//!
+//! At most 64 connections are served at once (the others get a 503 response), and the request line and headers must be
+//! sent within 10 seconds, in at most 8 KiB.
+
+use libtzfile::ZoneLoader;
+use std::{
+    env,
+    io::{BufRead, BufReader, Read, Write},
+    net::{TcpListener, TcpStream},
+    process,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    thread,
+    time::Duration,
+};
+
+const USAGE: &str = "usage: tzserver [--listen address:port]";
+const MAX_CONNECTIONS: usize = 64;
+// Request line and headers
+const MAX_REQUEST: u64 = 8192;
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+struct Response {
+    status: &'static str,
+    content_type: &'static str,
+    body: String,
+}
+
+// A connection being served, counted until dropped
+struct Connection(Arc<AtomicUsize>);
+
+impl Connection {
+    fn open(count: &Arc<AtomicUsize>) -> Option<Connection> {
+        if count.fetch_add(1, Ordering::SeqCst) < MAX_CONNECTIONS {
+            Some(Connection(Arc::clone(count)))
+        } else {
+            count.fetch_sub(1, Ordering::SeqCst);
+            None
+        }
+    }
+}
+
+impl Drop for Connection {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let address = match &args[..] {
+        [] => "127.0.0.1:8080",
+        [option, address] if option == "--listen" => address,
+        _ => {
+            eprintln!("{}", USAGE);
+            process::exit(2);
+        }
+    };
+    let loader = ZoneLoader::new(env::var("TZDIR").unwrap_or(String::from("/usr/share/zoneinfo")));
+    let zones = match loader.zones() {
+        Ok(zones) => zones,
+        Err(e) => {
+            eprintln!("tzserver: {}: {}", loader.root().display(), e);
+            process::exit(1);
+        }
+    };
+    let listener = match TcpListener::bind(address) {
+        Ok(listener) => listener,
+        Err(e) => {
+            eprintln!("tzserver: {}: {}", address, e);
+            process::exit(1);
+        }
+    };
+    let state = Arc::new((loader, zones));
+    let connections = Arc::new(AtomicUsize::new(0));
+    for mut stream in listener.incoming().flatten() {
+        // The client may have gone away: nothing to do
+        let _ = stream.set_read_timeout(Some(TIMEOUT));
+        let _ = stream.set_write_timeout(Some(TIMEOUT));
+        let connection = match Connection::open(&connections) {
+            Some(connection) => connection,
+            None => {
+                let _ = respond(
+                    &mut stream,
+                    error("503 Service Unavailable", "too many connections", false),
+                );
+                continue;
+            }
+        };
+        let state = Arc::clone(&state);
+        thread::spawn(move || {
+            let _connection = connection;
+            let (loader, zones) = &*state;
+            let _ = handle(stream, loader, zones);
+        });
+    }
+}
+
+fn handle(mut stream: TcpStream, loader: &ZoneLoader, zones: &[String]) -> std::io::Result<()> {
+    let client_ip = stream.peer_addr()?.ip().to_string();
+    let mut reader = BufReader::new((&stream).take(MAX_REQUEST));
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    // Headers are not used, but must fit in the limit
+    let mut complete = request_line.ends_with('\n');
+    let mut header = String::new();
+    while complete {
+        header.clear();
+        reader.read_line(&mut header)?;
+        complete = header.ends_with('\n');
+        if header.trim_end().is_empty() {
+            break;
+        }
+    }
+    let response = match request_line.split_whitespace().collect::<Vec<&str>>()[..] {
+        _ if !complete => error("400 Bad Request", "bad request", false),
+        ["GET", target, _] => route(target, &client_ip, loader, zones),
+        [_, _, _] => error("405 Method Not Allowed", "method not allowed", false),
+        _ => error("400 Bad Request", "bad request", false),
+    };
+    respond(&mut stream, response)
+}
+
+fn respond(stream: &mut TcpStream, response: Response) -> std::io::Result<()> {
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        response.status,
+        response.content_type,
+        response.body.len(),
+        response.body
+    )
+}
+
+fn route(target: &str, client_ip: &str, loader: &ZoneLoader, zones: &[String]) -> Response {
+    let path = target.split(['?', '#']).next().unwrap_or_default();
+    let (path, text) = match path.strip_suffix(".txt") {
+        Some(path) => (path, true),
+        None => (path, false),
+    };
+    let zone = match path.trim_end_matches('/').strip_prefix("/api/timezone") {
+        Some("") => return list(zones.iter(), text),
+        Some(zone) => match zone.strip_prefix('/') {
+            Some(zone) => zone,
+            None => return error("404 Not Found", "unknown location", text),
+        },
+        None => return error("404 Not Found", "unknown route", text),
+    };
+    if zones.iter().any(|z| z == zone) {
+        let tzinfo = loader.load(zone).and_then(|tz| tz.zoneinfo());
+        let json = tzinfo.map(|tzinfo| tzinfo.worldtimeapi(client_ip).to_json());
+        return match json {
+            Ok(Ok(json)) if text => ok("text/plain; charset=utf-8", fields(&json)),
+            Ok(Ok(json)) => ok("application/json; charset=utf-8", json),
+            _ => error("500 Internal Server Error", "zone data unavailable", text),
+        };
+    }
+    // An area (ie. America or America/Argentina)
+    let area = format!("{}/", zone);
+    let mut area_zones = zones.iter().filter(|z| z.starts_with(&area)).peekable();
+    if area_zones.peek().is_some() {
+        list(area_zones, text)
+    } else {
+        error("404 Not Found", "unknown location", text)
+    }
+}
+
+fn list<'a>(zones: impl Iterator<Item = &'a String>, text: bool) -> Response {
+    let zones: Vec<&String> = zones.collect();
+    if text {
+        let body: String = zones.iter().map(|z| format!("{}\n", z)).collect();
+        ok("text/plain; charset=utf-8", body)
+    } else {
+        let body = serde_json::to_string(&zones).unwrap_or_default();
+        ok("application/json; charset=utf-8", body)
+    }
+}
+
+// key: value lines of a JSON object, in the alphabetical order of the keys (serde_json's Map is sorted)
+fn fields(json: &str) -> String {
+    let object = match serde_json::from_str::<serde_json::Map<String, serde_json::Value>>(json) {
+        Ok(object) => object,
+        Err(_) => return String::new(),
+    };
+    object
+        .iter()
+        .map(|(key, value)| match value {
+            serde_json::Value::String(s) => format!("{}: {}\n", key, s),
+            serde_json::Value::Null => format!("{}: \n", key),
+            value => format!("{}: {}\n", key, value),
+        })
+        .collect()
+}
+
+fn ok(content_type: &'static str, body: String) -> Response {
+    Response {
+        status: "200 OK",
+        content_type,
+        body,
+    }
+}
+
+fn error(status: &'static str, message: &str, text: bool) -> Response {
+    if text {
+        Response {
+            status,
+            content_type: "text/plain; charset=utf-8",
+            body: format!("Error: {}\n", message),
+        }
+    } else {
+        Response {
+            status,
+            content_type: "application/json; charset=utf-8",
+            body: serde_json::json!({ "error": message }).to_string(),
+        }
+    }
+}
@@ -0,0 +1,266 @@
+//! C ABI (available with the capi feature, which includes the parse feature).
+//!
+//! Zones are opaque ```Tz``` handles, created by ```tz_from_bytes``` or ```tz_from_path``` and released by ```tz_free```.
+//! Functions return ```TZ_OK``` or an error code, described by ```tz_strerror```, and write their results through
+//! out pointers. Instants are numbers of seconds since the Unix epoch, and local times are given the same way, as if the
+//! local time were UTC. Panics do not cross the C boundary: they are reported as ```TZ_ERROR_INTERNAL```. The header is
+//! generated by cbindgen in ```include/libtzfile.h```.
+
+use crate::{Disambiguation, Tz, TzError};
+use chrono::{DateTime, NaiveDateTime, Utc};
+use std::{
+    ffi::{c_char, c_int, CStr},
+    panic::{self, AssertUnwindSafe},
+    slice,
+};
+
+/// Success
+pub const TZ_OK: c_int = 0;
+/// Invalid timezone
+pub const TZ_ERROR_INVALID_TIMEZONE: c_int = 1;
+/// Invalid TZfile
+pub const TZ_ERROR_INVALID_MAGIC: c_int = 2;
+/// Bad utf8 string
+pub const TZ_ERROR_BAD_UTF8_STRING: c_int = 3;
+/// Only V2 (and later) format is supported
+pub const TZ_ERROR_UNSUPPORTED_FORMAT: c_int = 4;
+/// No data matched the request
+pub const TZ_ERROR_NO_DATA: c_int = 5;
+/// Parsing error
+pub const TZ_ERROR_PARSE: c_int = 6;
+/// Empty string
+pub const TZ_ERROR_EMPTY_STRING: c_int = 7;
+/// Could not convert to json
+pub const TZ_ERROR_JSON: c_int = 8;
+/// Invalid format string
+pub const TZ_ERROR_INVALID_FORMAT: c_int = 9;
+/// Ambiguous local time
+pub const TZ_ERROR_AMBIGUOUS_LOCAL_TIME: c_int = 10;
+/// Local time skipped by a transition
+pub const TZ_ERROR_SKIPPED_LOCAL_TIME: c_int = 11;
+/// Ambiguous timezone abbreviation
+pub const TZ_ERROR_AMBIGUOUS_ABBREVIATION: c_int = 12;
+/// Offset inconsistent with the timezone
+pub const TZ_ERROR_INCONSISTENT_OFFSET: c_int = 13;
+/// Unsupported critical annotation
+pub const TZ_ERROR_UNSUPPORTED_ANNOTATION: c_int = 14;
+/// Null pointer or invalid argument
+pub const TZ_ERROR_INVALID_ARGUMENT: c_int = 15;
+/// Internal error (a bug of the library)
+pub const TZ_ERROR_INTERNAL: c_int = 16;
+
+/// Earlier instant for ambiguous local times, later instant for skipped local times
+pub const TZ_DISAMBIGUATION_COMPATIBLE: c_int = 0;
+/// Earlier instant
+pub const TZ_DISAMBIGUATION_EARLIER: c_int = 1;
+/// Later instant
+pub const TZ_DISAMBIGUATION_LATER: c_int = 2;
+/// Ambiguous or skipped local times are errors
+pub const TZ_DISAMBIGUATION_REJECT: c_int = 3;
+
+/// Size of the abbreviation buffer of TzLocalTimeType, terminating NUL included
+pub const TZ_ABBREVIATION_SIZE: usize = 16;
+
+/// Local time type in effect at an instant, as written by ```tz_lookup```.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TzLocalTimeType {
+    /// Offset to UTC, in seconds
+    pub utc_offset: i32,
+    /// Is DST observed ?
+    pub isdst: bool,
+    /// TZ abbreviation, NUL terminated
+    pub abbreviation: [c_char; TZ_ABBREVIATION_SIZE],
+}
+
+fn code(e: TzError) -> c_int {
+    match e {
+        TzError::InvalidTimezone => TZ_ERROR_INVALID_TIMEZONE,
+        TzError::InvalidMagic => TZ_ERROR_INVALID_MAGIC,
+        TzError::BadUtf8String => TZ_ERROR_BAD_UTF8_STRING,
+        TzError::UnsupportedFormat => TZ_ERROR_UNSUPPORTED_FORMAT,
+        TzError::NoData => TZ_ERROR_NO_DATA,
+        TzError::ParseError => TZ_ERROR_PARSE,
+        TzError::EmptyString => TZ_ERROR_EMPTY_STRING,
+        TzError::JsonError => TZ_ERROR_JSON,
+        TzError::InvalidFormat => TZ_ERROR_INVALID_FORMAT,
+        TzError::AmbiguousLocalTime => TZ_ERROR_AMBIGUOUS_LOCAL_TIME,
+        TzError::SkippedLocalTime => TZ_ERROR_SKIPPED_LOCAL_TIME,
+        TzError::AmbiguousAbbreviation => TZ_ERROR_AMBIGUOUS_ABBREVIATION,
+        TzError::InconsistentOffset => TZ_ERROR_INCONSISTENT_OFFSET,
+        TzError::UnsupportedAnnotation => TZ_ERROR_UNSUPPORTED_ANNOTATION,
+    }
+}
+
+// Runs f, writing its result through the out pointer. A panic of f is caught, as unwinding into C is undefined behavior.
+pub(crate) fn write<T>(out: *mut T, f: impl FnOnce() -> Result<T, c_int>) -> c_int {
+    if out.is_null() {
+        return TZ_ERROR_INVALID_ARGUMENT;
+    }
+    match panic::catch_unwind(AssertUnwindSafe(f)) {
+        Ok(Ok(value)) => {
+            // SAFETY: out is not null, and points to a T according to the caller
+            unsafe { out.write(value) };
+            TZ_OK
+        }
+        Ok(Err(code)) => code,
+        Err(_) => TZ_ERROR_INTERNAL,
+    }
+}
+
+// SAFETY: s is null or a NUL terminated string
+unsafe fn string<'a>(s: *const c_char) -> Result<&'a str, c_int> {
+    if s.is_null() {
+        return Err(TZ_ERROR_INVALID_ARGUMENT);
+    }
+    CStr::from_ptr(s)
+        .to_str()
+        .map_err(|_| TZ_ERROR_BAD_UTF8_STRING)
+}
+
+// SAFETY: tz is null or a handle returned by tz_from_bytes or tz_from_path
+unsafe fn tz<'a>(tz: *const Tz) -> Result<&'a Tz, c_int> {
+    tz.as_ref().ok_or(TZ_ERROR_INVALID_ARGUMENT)
+}
+
+fn instant(t: i64) -> Result<DateTime<Utc>, c_int> {
+    DateTime::from_timestamp(t, 0).ok_or(TZ_ERROR_NO_DATA)
+}
+
+/// Returns the static, NUL terminated message of an error code.
+#[no_mangle]
+pub extern "C" fn tz_strerror(code: c_int) -> *const c_char {
+    let message = match code {
+        TZ_OK => c"Success",
+        TZ_ERROR_INVALID_TIMEZONE => c"Invalid timezone",
+        TZ_ERROR_INVALID_MAGIC => c"Invalid TZfile",
+        TZ_ERROR_BAD_UTF8_STRING => c"Bad utf8 string",
+        TZ_ERROR_UNSUPPORTED_FORMAT => c"Only V2 (and later) format is supported",
+        TZ_ERROR_NO_DATA => c"No data matched the request",
+        TZ_ERROR_PARSE => c"Parsing error",
+        TZ_ERROR_EMPTY_STRING => c"Empty string",
+        TZ_ERROR_JSON => c"Could not convert to json",
+        TZ_ERROR_INVALID_FORMAT => c"Invalid format string",
+        TZ_ERROR_AMBIGUOUS_LOCAL_TIME => c"Ambiguous local time",
+        TZ_ERROR_SKIPPED_LOCAL_TIME => c"Local time skipped by a transition",
+        TZ_ERROR_AMBIGUOUS_ABBREVIATION => c"Ambiguous timezone abbreviation",
+        TZ_ERROR_INCONSISTENT_OFFSET => c"Offset inconsistent with the timezone",
+        TZ_ERROR_UNSUPPORTED_ANNOTATION => c"Unsupported critical annotation",
+        TZ_ERROR_INVALID_ARGUMENT => c"Null pointer or invalid argument",
+        TZ_ERROR_INTERNAL => c"Internal error",
+        _ => c"Unknown error code",
+    };
+    message.as_ptr()
+}
+
+/// Parses a TZfile from its content (len bytes at buf). The name is the zone's name (ie. Europe/Paris).
+/// On success, the handle written to out must be released with ```tz_free```.
+///
+/// # Safety
+/// buf must point to len readable bytes, name must be a NUL terminated string, and out must be writable.
+#[no_mangle]
+pub unsafe extern "C" fn tz_from_bytes(
+    buf: *const u8,
+    len: usize,
+    name: *const c_char,
+    out: *mut *mut Tz,
+) -> c_int {
+    write(out, || {
+        if buf.is_null() {
+            return Err(TZ_ERROR_INVALID_ARGUMENT);
+        }
+        let buf = slice::from_raw_parts(buf, len);
+        let tz = Tz::from_bytes(buf, string(name)?).map_err(code)?;
+        Ok(Box::into_raw(Box::new(tz)))
+    })
+}
+
+/// Reads and parses a TZfile (ie. /usr/share/zoneinfo/Europe/Paris).
+/// On success, the handle written to out must be released with ```tz_free```.
+///
+/// # Safety
+/// path must be a NUL terminated string, and out must be writable.
+#[no_mangle]
+pub unsafe extern "C" fn tz_from_path(path: *const c_char, out: *mut *mut Tz) -> c_int {
+    write(out, || {
+        let tz = Tz::new(string(path)?).map_err(code)?;
+        Ok(Box::into_raw(Box::new(tz)))
+    })
+}
+
+/// Releases a handle. Null handles are ignored.
+///
+/// # Safety
+/// tz must be null or a handle returned by ```tz_from_bytes``` or ```tz_from_path```, not released yet.
+#[no_mangle]
+pub unsafe extern "C" fn tz_free(tz: *mut Tz) {
+    if !tz.is_null() {
+        let tz = Box::from_raw(tz);
+        // Nothing to report
+        let _ = panic::catch_unwind(AssertUnwindSafe(|| drop(tz)));
+    }
+}
+
+/// Writes the local time type (offset to UTC, DST and abbreviation) in effect at an instant.
+///
+/// # Safety
+/// tz must be a live handle, and out must be writable.
+#[no_mangle]
+pub unsafe extern "C" fn tz_lookup(tz: *const Tz, utc: i64, out: *mut TzLocalTimeType) -> c_int {
+    write(out, || {
+        let ltt = self::tz(tz)?.lookup(instant(utc)?);
+        let mut abbreviation = [0; TZ_ABBREVIATION_SIZE];
+        for (c, &b) in abbreviation
+            .iter_mut()
+            .zip(ltt.abbreviation.as_bytes())
+            .take(TZ_ABBREVIATION_SIZE - 1)
+        {
+            *c = b as c_char;
+        }
+        Ok(TzLocalTimeType {
+            utc_offset: ltt.utc_offset as i32,
+            isdst: ltt.isdst,
+            abbreviation,
+        })
+    })
+}
+
+/// Converts an instant to the local time.
+///
+/// # Safety
+/// tz must be a live handle, and out must be writable.
+#[no_mangle]
+pub unsafe extern "C" fn tz_utc_to_local(tz: *const Tz, utc: i64, out: *mut i64) -> c_int {
+    write(out, || {
+        let utc_offset = self::tz(tz)?.lookup(instant(utc)?).utc_offset as i64;
+        utc.checked_add(utc_offset).ok_or(TZ_ERROR_NO_DATA)
+    })
+}
+
+/// Converts a local time to an instant. Ambiguous and skipped local times are handled according to the disambiguation
+/// policy (one of the TZ_DISAMBIGUATION_ constants).
+///
+/// # Safety
+/// tz must be a live handle, and out must be writable.
+#[no_mangle]
+pub unsafe extern "C" fn tz_local_to_utc(
+    tz: *const Tz,
+    local: i64,
+    disambiguation: c_int,
+    out: *mut i64,
+) -> c_int {
+    write(out, || {
+        let disambiguation = match disambiguation {
+            TZ_DISAMBIGUATION_COMPATIBLE => Disambiguation::Compatible,
+            TZ_DISAMBIGUATION_EARLIER => Disambiguation::Earlier,
+            TZ_DISAMBIGUATION_LATER => Disambiguation::Later,
+            TZ_DISAMBIGUATION_REJECT => Disambiguation::Reject,
+            _ => return Err(TZ_ERROR_INVALID_ARGUMENT),
+        };
+        let local: NaiveDateTime = instant(local)?.naive_utc();
+        let utc = self::tz(tz)?
+            .local_to_utc(local, disambiguation)
+            .map_err(code)?;
+        Ok(utc.timestamp())
+    })
+}
@@ -0,0 +1,387 @@
+//! Eras of a zone (available with the parse or json features).
+//!
+//! The periods between the transitions are grouped into eras of constant standard offset and abbreviation pattern. The
+//! save amount of a DST period is its offset to UTC minus the standard offset of the nearest standard period. The TZ
+//! string footer's rule is in force in the last era, from the first year of the recorded transitions which follow it.
+//!
+//! TZfiles only record offsets to UTC, isdst flags and abbreviations: standard offsets, save amounts and formats are
+//! inferred from them, and are approximations of the tzdata source's.
+
+use crate::{ical, posix, PosixTz, String, ToString, Tz, TzError, Vec};
+use chrono::{DateTime, Duration, Utc};
+use core::cmp::Reverse;
+use std::{format, ops::Range};
+
+/// A period of constant standard offset and abbreviation pattern in the history of a zone, as returned by ```Tz::eras```.
+/// It approximates a Zone line of the tzdata source: the standard offset, save amount and format are inferred from the
+/// TZfile, and may differ from the Zone line's (ie. Paris in 1944-1945, a 1:00 standard offset and WE%sT, where tzdata
+/// has 0:00 with double summer time).
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+pub struct Era {
+    /// Start of the era, None for the first one
+    pub start: Option<DateTime<Utc>>,
+    /// End of the era, None for the last one
+    pub end: Option<DateTime<Utc>>,
+    /// Standard offset to UTC, in seconds
+    pub std_offset: isize,
+    /// Are DST rules in force (DST observed during the era, or the footer rule in the last era) ?
+    pub dst_rules: bool,
+    /// DST save amount, in seconds: the footer rule's one in the last era, otherwise the most frequent one (0 without DST)
+    pub save: isize,
+    /// Abbreviation pattern, as the FORMAT field of a Zone line (ie. LMT, CE%sT, GMT/BST or %z)
+    pub format: String,
+}
+
+// A period between two transitions changing the local time type
+pub(crate) struct Period {
+    // UTC time and offset to UTC before the transition starting it, None for the first period
+    pub(crate) start: Option<(i64, isize)>,
+    pub(crate) utc_offset: isize,
+    pub(crate) isdst: bool,
+    pub(crate) abbreviation: String,
+    pub(crate) std_offset: isize,
+}
+
+// An era, as a run of periods (a Zone line of the tzdata source)
+pub(crate) struct Segment {
+    // None for the first era
+    pub(crate) start: Option<i64>,
+    pub(crate) periods: Range<usize>,
+    pub(crate) std_offset: isize,
+    pub(crate) std_abbr: Option<String>,
+    // Save amounts and abbreviations of the DST periods
+    pub(crate) dst: Vec<(isize, String)>,
+    // Are all abbreviations the numeric offsets to UTC (ie. +03) ?
+    pub(crate) numeric: bool,
+    // Is the footer rule in force at the end of the era ?
+    pub(crate) footer: bool,
+}
+
+impl Segment {
+    fn new(start: Option<i64>, first: usize, std_offset: isize) -> Segment {
+        Segment {
+            start,
+            periods: first..first,
+            std_offset,
+            std_abbr: None,
+            dst: Vec::new(),
+            numeric: true,
+            footer: false,
+        }
+    }
+
+    // Does a local time type with this standard offset belong to the era ? An abbreviation breaking the pattern of
+    // the previous ones (ie. WEMT after CET and CEST) starts a new era.
+    fn accepts(&self, std_offset: isize, isdst: bool, abbreviation: &str) -> bool {
+        let mut abbreviations = self.abbreviations();
+        let prefix = common_prefix(&abbreviations);
+        if !abbreviations.contains(&abbreviation) {
+            abbreviations.push(abbreviation);
+        }
+        self.std_offset == std_offset
+            && (isdst || self.std_abbr.as_ref().is_none_or(|a| a == abbreviation))
+            && (abbreviations.len() < 3 || prefix == 0 || common_prefix(&abbreviations) > 0)
+    }
+
+    fn add(&mut self, utc_offset: isize, isdst: bool, abbreviation: &str) {
+        if isdst {
+            let dst = (utc_offset - self.std_offset, abbreviation.to_string());
+            if !self.dst.contains(&dst) {
+                self.dst.push(dst);
+            }
+        } else {
+            self.std_abbr = Some(abbreviation.to_string());
+        }
+        self.numeric &= abbreviation == numeric(utc_offset);
+    }
+
+    // Distinct abbreviations, the standard one first
+    fn abbreviations(&self) -> Vec<&str> {
+        let mut abbreviations: Vec<&str> = Vec::new();
+        for abbreviation in self.std_abbr.iter().chain(self.dst.iter().map(|(_, a)| a)) {
+            if !abbreviations.contains(&abbreviation.as_str()) {
+                abbreviations.push(abbreviation);
+            }
+        }
+        abbreviations
+    }
+
+    pub(crate) fn format(&self) -> Format {
+        let abbreviations = self.abbreviations();
+        if self.numeric {
+            return Format::Numeric;
+        }
+        if let [abbreviation] = abbreviations[..] {
+            return Format::Literal(abbreviation.to_string());
+        }
+        // Common prefix and suffix of the abbreviations, the letters being what is left in between (ie. CE%sT)
+        let first = abbreviations[0];
+        let prefix = common_prefix(&abbreviations);
+        let shortest = abbreviations.iter().map(|a| a.len()).min().unwrap_or(0);
+        let suffix = (0..=shortest - prefix)
+            .rev()
+            .find(|&n| {
+                let end = first.get(first.len() - n..);
+                abbreviations.iter().all(|a| a.get(a.len() - n..) == end)
+            })
+            .unwrap_or(0);
+        match (&self.std_abbr, &self.dst[..]) {
+            _ if prefix > 0 => Format::Letters(
+                first[..prefix].to_string(),
+                first
+                    .get(first.len() - suffix..)
+                    .unwrap_or_default()
+                    .to_string(),
+            ),
+            (Some(std_abbr), [(_, dst_abbr)]) => Format::Slash(std_abbr.clone(), dst_abbr.clone()),
+            _ => Format::Letters(String::new(), String::new()),
+        }
+    }
+}
+
+// FORMAT field of a Zone line
+pub(crate) enum Format {
+    Literal(String),
+    // %z
+    Numeric,
+    // Standard and DST abbreviations
+    Slash(String, String),
+    // Prefix and suffix around the rules' letters
+    Letters(String, String),
+}
+
+impl Format {
+    // LETTER/S field of a rule giving an abbreviation
+    pub(crate) fn letter(&self, abbreviation: &str) -> String {
+        match self {
+            Format::Letters(prefix, suffix) => {
+                let end = abbreviation.len().saturating_sub(suffix.len());
+                match abbreviation.get(prefix.len()..end) {
+                    Some("") | None => String::from("-"),
+                    Some(letter) => letter.to_string(),
+                }
+            }
+            _ => String::from("-"),
+        }
+    }
+}
+
+impl core::fmt::Display for Format {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Format::Literal(abbreviation) => write!(f, "{}", abbreviation),
+            Format::Numeric => write!(f, "%z"),
+            Format::Slash(std_abbr, dst_abbr) => write!(f, "{}/{}", std_abbr, dst_abbr),
+            Format::Letters(prefix, suffix) => write!(f, "{}%s{}", prefix, suffix),
+        }
+    }
+}
+
+// Periods of a zone grouped in eras, with the footer in force in the last era and the first year of its rules (None
+// when it is in force from the beginning)
+pub(crate) struct Segmentation<'a> {
+    pub(crate) periods: Vec<Period>,
+    pub(crate) segments: Vec<Segment>,
+    pub(crate) footer: Option<(&'a PosixTz, Option<i32>)>,
+}
+
+pub(crate) fn segmentation(tz: &Tz) -> Result<Segmentation<'_>, TzError> {
+    let recorded_end = match tz.tzh_timecnt_data.last() {
+        Some(&r) => Some(DateTime::<Utc>::from_timestamp(r, 0).ok_or(TzError::NoData)?),
+        None => None,
+    };
+    // Footer with DST rules, first year and first transition time of its rules. The recorded transitions which already
+    // follow the footer rule (ie. up to 2037 in "fat" TZfiles) are covered by it from the year of the first one.
+    let footer = match &tz.tz_footer {
+        Some(footer) if footer.dst.is_some() => {
+            let start = ical::rule_start(tz, recorded_end)
+                .or(recorded_end.map(|r| r + Duration::seconds(1)))
+                .map(|s| s.timestamp());
+            let mut year = start.map_or(1970, posix::year_of);
+            if let Some(start) = start {
+                if footer
+                    .transitions(year)
+                    .iter()
+                    .flatten()
+                    .any(|&(t, _)| t < start)
+                {
+                    year += 1;
+                }
+            }
+            // Permanent DST is written as a standard time
+            footer.transitions(year)[0].map(|(cut, _)| match start {
+                Some(_) => (footer, Some(year), cut),
+                None => (footer, None, i64::MIN),
+            })
+        }
+        _ => None,
+    };
+    let cut = footer.map_or(i64::MAX, |(_, _, cut)| cut);
+
+    let transitions: Vec<_> = match recorded_end {
+        Some(recorded_end) => tz
+            .transitions_from(DateTime::<Utc>::MIN_UTC)
+            .take_while(|tt| {
+                tt.time.timestamp() < cut && (footer.is_some() || tt.time <= recorded_end)
+            })
+            .filter(|tt| tz.changes_local_type(tt))
+            .collect(),
+        None => Vec::new(),
+    };
+    let initial = match transitions.first() {
+        Some(tt) => Some(tz.local_type_at(tt.time.timestamp() - 1)),
+        None if cut == i64::MIN => None,
+        None if cut == i64::MAX => Some(tz.local_type_at(0)),
+        None => Some(tz.local_type_at(cut - 1)),
+    };
+    let mut periods: Vec<Period> = initial
+        .map(|(utc_offset, isdst, abbreviation)| Period {
+            start: None,
+            utc_offset,
+            isdst,
+            abbreviation: abbreviation.to_string(),
+            std_offset: utc_offset,
+        })
+        .into_iter()
+        .chain(transitions.into_iter().map(|tt| Period {
+            start: Some((tt.time.timestamp(), tt.old_utc_offset)),
+            utc_offset: tt.new_utc_offset,
+            isdst: tt.isdst,
+            abbreviation: tt.abbreviation,
+            std_offset: tt.new_utc_offset,
+        }))
+        .collect();
+
+    // Standard offset of the DST periods: the one of the nearest standard period before or after, giving the smallest
+    // non zero save amount (ie. 1 hour rather than 2 when the standard offset changed along with a DST start)
+    for i in 0..periods.len() {
+        if !periods[i].isdst {
+            continue;
+        }
+        let utc_offset = periods[i].utc_offset;
+        let previous = periods[..i]
+            .iter()
+            .rev()
+            .find(|p| !p.isdst)
+            .map(|p| p.utc_offset);
+        let next = periods[i + 1..]
+            .iter()
+            .find(|p| !p.isdst)
+            .map(|p| p.utc_offset)
+            .or(tz.tz_footer.as_ref().map(|f| f.std_utoff));
+        periods[i].std_offset = previous
+            .into_iter()
+            .chain(next)
+            .min_by_key(|&s| (s == utc_offset, (utc_offset - s).abs()))
+            .unwrap_or(utc_offset - 3600);
+    }
+
+    // Without DST rules in the footer, DST is no longer observed after the last DST period: it ends its era
+    let last_dst = periods
+        .iter()
+        .rposition(|p| p.isdst)
+        .filter(|_| footer.is_none());
+    let mut segments: Vec<Segment> = Vec::new();
+    for (i, p) in periods.iter().enumerate() {
+        if !segments
+            .last()
+            .is_some_and(|s| s.accepts(p.std_offset, p.isdst, &p.abbreviation))
+            || last_dst.is_some_and(|k| i == k + 1)
+        {
+            segments.push(Segment::new(p.start.map(|(t, _)| t), i, p.std_offset));
+        }
+        if let Some(segment) = segments.last_mut() {
+            segment.periods.end = i + 1;
+            segment.add(p.utc_offset, p.isdst, &p.abbreviation);
+        }
+    }
+    if let Some((footer, _, cut)) = footer {
+        let (dst_offset, _, dst_abbr) = footer.ttinfo(true);
+        if !segments
+            .last()
+            .is_some_and(|s| s.accepts(footer.std_utoff, false, &footer.std_abbr))
+        {
+            let start = (cut != i64::MIN).then_some(cut);
+            segments.push(Segment::new(start, periods.len(), footer.std_utoff));
+        }
+        if let Some(segment) = segments.last_mut() {
+            segment.footer = true;
+            segment.add(footer.std_utoff, false, &footer.std_abbr);
+            segment.add(dst_offset, true, dst_abbr);
+        }
+    }
+    Ok(Segmentation {
+        periods,
+        segments,
+        footer: footer.map(|(footer, from, _)| (footer, from)),
+    })
+}
+
+pub(crate) fn eras(tz: &Tz) -> Result<Vec<Era>, TzError> {
+    let Segmentation {
+        periods,
+        segments,
+        footer,
+    } = segmentation(tz)?;
+    let mut eras = Vec::new();
+    for (i, segment) in segments.iter().enumerate() {
+        let save = match footer {
+            Some((footer, _)) if segment.footer => footer.ttinfo(true).0 - footer.std_utoff,
+            _ => {
+                let saves: Vec<isize> = periods[segment.periods.clone()]
+                    .iter()
+                    .filter(|p| p.isdst)
+                    .map(|p| p.utc_offset - p.std_offset)
+                    .collect();
+                saves
+                    .iter()
+                    .copied()
+                    .min_by_key(|&s| Reverse(saves.iter().filter(|&&x| x == s).count()))
+                    .unwrap_or(0)
+            }
+        };
+        eras.push(Era {
+            start: segment.start.map(timestamp).transpose()?,
+            end: segments
+                .get(i + 1)
+                .and_then(|s| s.start)
+                .map(timestamp)
+                .transpose()?,
+            std_offset: segment.std_offset,
+            dst_rules: segment.footer || !segment.dst.is_empty(),
+            save,
+            format: segment.format().to_string(),
+        });
+    }
+    Ok(eras)
+}
+
+fn timestamp(t: i64) -> Result<DateTime<Utc>, TzError> {
+    DateTime::<Utc>::from_timestamp(t, 0).ok_or(TzError::NoData)
+}
+
+// Length of the common prefix of abbreviations
+fn common_prefix(abbreviations: &[&str]) -> usize {
+    let shortest = abbreviations.iter().map(|a| a.len()).min().unwrap_or(0);
+    (0..=shortest)
+        .rev()
+        .find(|&n| {
+            abbreviations
+                .iter()
+                .all(|a| a.get(..n) == abbreviations[0].get(..n))
+        })
+        .unwrap_or(0)
+}
+
+// Abbreviation written by zic's %z format for an offset to UTC
+fn numeric(utc_offset: isize) -> String {
+    let sign = if utc_offset < 0 { '-' } else { '+' };
+    let s = utc_offset.abs();
+    let (h, m, sec) = (s / 3600, s / 60 % 60, s % 60);
+    match (m, sec) {
+        (0, 0) => format!("{}{:02}", sign, h),
+        (m, 0) => format!("{}{:02}{:02}", sign, h, m),
+        (m, sec) => format!("{}{:02}{:02}{:02}", sign, h, m, sec),
+    }
+}
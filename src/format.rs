@@ -0,0 +1,84 @@
+//! strftime-like formatting of an instant in a zone (available with the parse or json features).
+//!
+//! The zone-dependent directives are rendered from the TZfile data, the other ones are handed to chrono:
+//! - ```%Z```: TZ abbreviation in effect (ie. LMT, CET, CEST)
+//! - ```%z```: offset to UTC as +hhmm, followed by the seconds if any (+hhmmss)
+//! - ```%:z```: offset to UTC as +hh:mm, followed by the seconds if any (+hh:mm:ss)
+//! - ```%::z```: offset to UTC as +hh:mm:ss
+//! - ```%:::z```: offset to UTC with the minimal precision (+hh, +hh:mm or +hh:mm:ss)
+//! - ```%s```: seconds since the Unix epoch
+
+use crate::{String, ToString, TzError};
+use chrono::format::{Item, StrftimeItems};
+use chrono::{DateTime, FixedOffset};
+use std::{fmt::Write, format};
+
+pub(crate) fn strftime(
+    local: &DateTime<FixedOffset>,
+    abbreviation: &str,
+    fmt: &str,
+) -> Result<String, TzError> {
+    let utc_offset = local.offset().local_minus_utc();
+    // Format string handed to chrono, the zone-dependent directives being replaced with their value
+    let mut chrono_fmt = String::with_capacity(fmt.len());
+    let mut rest = fmt;
+    while let Some(i) = rest.find('%') {
+        chrono_fmt.push_str(&rest[..i]);
+        rest = &rest[i..];
+        let (value, len) = if rest.starts_with("%%") {
+            (String::from("%%"), 2)
+        } else if rest.starts_with("%Z") {
+            (abbreviation.replace('%', "%%"), 2)
+        } else if rest.starts_with("%z") {
+            (offset(utc_offset, "", false), 2)
+        } else if rest.starts_with("%:z") {
+            (offset(utc_offset, ":", false), 3)
+        } else if rest.starts_with("%::z") {
+            (offset(utc_offset, ":", true), 4)
+        } else if rest.starts_with("%:::z") {
+            (minimal_offset(utc_offset), 5)
+        } else if rest.starts_with("%s") {
+            (local.timestamp().to_string(), 2)
+        } else {
+            (String::from("%"), 1)
+        };
+        chrono_fmt.push_str(&value);
+        rest = &rest[len..];
+    }
+    chrono_fmt.push_str(rest);
+
+    let items = StrftimeItems::new(&chrono_fmt);
+    if items.clone().any(|item| matches!(item, Item::Error)) {
+        return Err(TzError::InvalidFormat);
+    }
+    let mut formatted = String::new();
+    write!(formatted, "{}", local.format_with_items(items)).map_err(|_| TzError::InvalidFormat)?;
+    Ok(formatted)
+}
+
+fn sign_hms(utc_offset: i32) -> (char, i32, i32, i32) {
+    let sign = if utc_offset < 0 { '-' } else { '+' };
+    let s = utc_offset.abs();
+    (sign, s / 3600, s / 60 % 60, s % 60)
+}
+
+// +hhmm[ss] or +hh:mm[:ss], the seconds being always written if required
+pub(crate) fn offset(utc_offset: i32, separator: &str, seconds: bool) -> String {
+    let (sign, h, m, s) = sign_hms(utc_offset);
+    if seconds || s != 0 {
+        format!("{sign}{h:02}{separator}{m:02}{separator}{s:02}")
+    } else {
+        format!("{sign}{h:02}{separator}{m:02}")
+    }
+}
+
+fn minimal_offset(utc_offset: i32) -> String {
+    let (sign, h, m, s) = sign_hms(utc_offset);
+    if s != 0 {
+        format!("{sign}{h:02}:{m:02}:{s:02}")
+    } else if m != 0 {
+        format!("{sign}{h:02}:{m:02}")
+    } else {
+        format!("{sign}{h:02}")
+    }
+}
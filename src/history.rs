@@ -0,0 +1,49 @@
+//! Full history of a zone, for JSON export (available with the json feature).
+
+use crate::{LeapSecond, PosixTz, String, Transition, Tz, Vec};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// The whole history of a zone, as returned by ```Tz::history```: it contains everything needed to convert times
+/// without the TZfile (ie. offline, in a web frontend).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ZoneHistory {
+    /// Timezone name
+    pub timezone: String,
+    /// TZfile format version (2, 3 or 4)
+    pub version: u8,
+    /// Recorded transition times, with the offsets to UTC before and after them
+    pub transitions: Vec<Transition>,
+    /// Abbreviations table
+    pub abbreviations: Vec<String>,
+    /// TZ string footer, which gives the zone's rule after the last transition time (the raw field being the TZ string)
+    pub footer: Option<PosixTz>,
+    /// Leap second records
+    pub leap_seconds: Vec<LeapSecond>,
+}
+
+impl ZoneHistory {
+    /// Transforms the ZoneHistory struct to a JSON string
+    pub fn to_json(&self) -> Result<String, serde_json::error::Error> {
+        serde_json::to_string(self)
+    }
+}
+
+pub(crate) fn history(tz: &Tz) -> ZoneHistory {
+    // The forward iteration goes on with the footer transitions: stops at the last recorded one
+    let transitions = match tz.tzh_timecnt_data.last() {
+        Some(&last) => tz
+            .transitions_from(DateTime::<Utc>::MIN_UTC)
+            .take_while(|tt| tt.time.timestamp() <= last)
+            .collect(),
+        None => Vec::new(),
+    };
+    ZoneHistory {
+        timezone: tz.name.clone(),
+        version: tz.tzh_version,
+        transitions,
+        abbreviations: tz.tz_abbr.clone(),
+        footer: tz.tz_footer.clone(),
+        leap_seconds: tz.tzh_leapcnt_data.clone(),
+    }
+}
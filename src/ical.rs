@@ -0,0 +1,783 @@
+//! iCalendar (RFC 5545) VTIMEZONE export and import (available with the parse or json features).
+//!
+//! Export: the local time type in effect at the start year is the first observance. The recorded transition times that
+//! follow are grouped into STANDARD and DAYLIGHT observances with RDATEs, and the TZ string footer gives the RRULEs.
+//!
+//! Import: the DTSTART, RDATEs and RRULE occurrences of the observances are the transition times. The rules in force
+//! (without UNTIL nor COUNT) become the TZ string footer when they can be written as one.
+
+use crate::{
+    format, Disambiguation, PosixDate, PosixDst, PosixTz, String, ToString, Transition, Ttinfo, Tz,
+    TzError, Vec,
+};
+use chrono::{
+    DateTime, Datelike, Duration, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, TimeZone,
+    Timelike, Utc,
+};
+use std::format;
+
+// Number of years of RDATEs listed when a footer rule cannot be written as an RRULE
+const RDATE_YEARS: i32 = 50;
+
+// Largest RRULE INTERVAL accepted, the Gregorian calendar repeating itself every 400 years
+const MAX_INTERVAL: i32 = 400;
+
+const WEEKDAYS: [&str; 7] = ["SU", "MO", "TU", "WE", "TH", "FR", "SA"];
+
+// A STANDARD or DAYLIGHT sub-component
+struct Observance {
+    isdst: bool,
+    offset_from: isize,
+    offset_to: isize,
+    abbreviation: String,
+    // Local times (before the transition), the first one being DTSTART and the other ones RDATEs
+    onsets: Vec<String>,
+    rrule: Option<String>,
+}
+
+impl Observance {
+    fn new(tt: &Transition, onset: String) -> Observance {
+        Observance {
+            isdst: tt.isdst,
+            offset_from: tt.old_utc_offset,
+            offset_to: tt.new_utc_offset,
+            abbreviation: tt.abbreviation.clone(),
+            onsets: Vec::from([onset]),
+            rrule: None,
+        }
+    }
+
+    fn write(&self, ics: &mut String) {
+        let component = if self.isdst { "DAYLIGHT" } else { "STANDARD" };
+        ics.push_str(&format!("BEGIN:{}\r\n", component));
+        ics.push_str(&format!("TZOFFSETFROM:{}\r\n", offset(self.offset_from)));
+        ics.push_str(&format!("TZOFFSETTO:{}\r\n", offset(self.offset_to)));
+        ics.push_str(&format!("TZNAME:{}\r\n", self.abbreviation));
+        ics.push_str(&format!("DTSTART:{}\r\n", self.onsets[0]));
+        if let Some(rrule) = &self.rrule {
+            ics.push_str(&format!("RRULE:{}\r\n", rrule));
+        }
+        for rdate in &self.onsets[1..] {
+            ics.push_str(&format!("RDATE:{}\r\n", rdate));
+        }
+        ics.push_str(&format!("END:{}\r\n", component));
+    }
+}
+
+pub(crate) fn vtimezone(tz: &Tz, start_year: i32) -> Result<String, TzError> {
+    let start = NaiveDate::from_ymd_opt(start_year, 1, 1)
+        .and_then(|d| d.and_hms_opt(0, 0, 0))
+        .ok_or(TzError::NoData)?;
+    let start_utc = tz.local_to_utc(start, Disambiguation::Compatible)?;
+
+    // Local time type in effect at the start year
+    let ltt = tz.lookup(start_utc);
+    let mut observances = Vec::from([Observance {
+        isdst: ltt.isdst,
+        offset_from: ltt.utc_offset,
+        offset_to: ltt.utc_offset,
+        abbreviation: ltt.abbreviation,
+        onsets: Vec::from([start.format("%Y%m%dT%H%M%S").to_string()]),
+        rrule: None,
+    }]);
+
+    // Recorded transition times, grouped by local time type change. The last ones may already follow the footer rule
+    // (ie. up to 2037 in "fat" TZfiles): they are covered by its RRULEs.
+    let recorded_end = match tz.tzh_timecnt_data.last() {
+        Some(&r) => Some(timestamp(r)?),
+        None => None,
+    };
+    let rule_start = rule_start(tz, recorded_end);
+    let recorded = tz
+        .transitions_from(start_utc)
+        .take_while(|tt| {
+            rule_start.map_or(recorded_end.is_some_and(|r| tt.time <= r), |s| tt.time < s)
+        })
+        .filter(|tt| tt.time > start_utc && tz.changes_local_type(tt));
+    for tt in recorded {
+        let onset = onset(&tt);
+        match observances[1..].iter_mut().find(|o| {
+            o.isdst == tt.isdst
+                && o.offset_from == tt.old_utc_offset
+                && o.offset_to == tt.new_utc_offset
+                && o.abbreviation == tt.abbreviation
+        }) {
+            Some(o) => o.onsets.push(onset),
+            None => observances.push(Observance::new(&tt, onset)),
+        }
+    }
+
+    // Rules of the TZ string footer, from their first DST start and end
+    if let Some(dst) = tz.tz_footer.as_ref().and_then(|f| f.dst.as_ref()) {
+        let from = match (rule_start, recorded_end) {
+            (Some(s), _) => start_utc.max(s),
+            (None, Some(r)) => start_utc.max(r + Duration::seconds(1)),
+            (None, None) => start_utc,
+        };
+        let generated = tz
+            .transitions_from(from)
+            .filter(|tt| tt.time > start_utc)
+            .take(2);
+        for tt in generated {
+            let mut observance = Observance::new(&tt, onset(&tt));
+            match rrule(dst, tt.isdst) {
+                Some(rrule) => observance.rrule = Some(rrule),
+                // Not expressible as an RRULE: the transitions are listed
+                None => observance.onsets.extend(
+                    tz.transitions_from(tt.time)
+                        .skip(1)
+                        .take_while(|t| t.time.year() < tt.time.year() + RDATE_YEARS)
+                        .filter(|t| t.isdst == tt.isdst)
+                        .map(|t| onset(&t)),
+                ),
+            }
+            observances.push(observance);
+        }
+    }
+
+    let mut ics = String::from("BEGIN:VTIMEZONE\r\n");
+    ics.push_str(&format!("TZID:{}\r\n", tz.name));
+    for observance in &observances {
+        observance.write(&mut ics);
+    }
+    ics.push_str("END:VTIMEZONE\r\n");
+    Ok(ics)
+}
+
+// Time of the first recorded transition of the table's tail which follows the footer rule: from it, the recorded
+// transitions are exactly the ones generated by the footer
+pub(crate) fn rule_start(tz: &Tz, recorded_end: Option<DateTime<Utc>>) -> Option<DateTime<Utc>> {
+    let footer = tz.tz_footer.as_ref()?;
+    let mut start = None;
+    let recorded = tz
+        .transitions_before(recorded_end? + Duration::seconds(1))
+        .filter(|tt| tz.changes_local_type(tt));
+    for tt in recorded {
+        let t = tt.time.timestamp();
+        let generated = footer.transitions(tt.time.year());
+        let (utc_offset, isdst, abbreviation) = footer.ttinfo(tt.isdst);
+        let matching = generated.contains(&Some((t, tt.isdst)))
+            && (utc_offset, isdst, abbreviation) == (tt.new_utc_offset, tt.isdst, &tt.abbreviation)
+            && footer.ttinfo(!tt.isdst).0 == tt.old_utc_offset;
+        // A year without a transition of the footer (ie. DST not observed) interrupts the tail
+        let following = start
+            .is_none_or(|s: DateTime<Utc>| previous_generated(footer, s.timestamp()) == Some(t));
+        if !matching || !following {
+            break;
+        }
+        start = Some(tt.time);
+    }
+    start
+}
+
+// Footer transition preceding a timestamp
+fn previous_generated(footer: &PosixTz, t: i64) -> Option<i64> {
+    let year = crate::posix::year_of(t);
+    footer
+        .transitions(year - 1)
+        .into_iter()
+        .chain(footer.transitions(year))
+        .flatten()
+        .map(|(g, _)| g)
+        .filter(|&g| g < t)
+        .max()
+}
+
+// Local time of a transition, before it occurs
+fn onset(tt: &Transition) -> String {
+    (tt.time + Duration::seconds(tt.old_utc_offset as i64))
+        .format("%Y%m%dT%H%M%S")
+        .to_string()
+}
+
+// +hhmm, or +hhmmss for offsets with seconds
+fn offset(utc_offset: isize) -> String {
+    format::offset(utc_offset as i32, "", false)
+}
+
+fn timestamp(t: i64) -> Result<DateTime<Utc>, TzError> {
+    Utc.timestamp_opt(t, 0).single().ok_or(TzError::NoData)
+}
+
+// RRULE of the DST start or end of a footer rule. The rule's time of day may be negative or exceed 24 hours
+// (ie. M3.4.4/26, the Friday before the last Sunday), which shifts the day.
+fn rrule(dst: &PosixDst, isdst: bool) -> Option<String> {
+    let (date, time) = if isdst {
+        (dst.start, dst.start_time)
+    } else {
+        (dst.end, dst.end_time)
+    };
+    let shift = time.div_euclid(86400);
+    match date {
+        PosixDate::MonthWeekDay {
+            month,
+            week,
+            weekday,
+        } => {
+            let weekday = WEEKDAYS[(weekday as isize + shift).rem_euclid(7) as usize];
+            if shift == 0 {
+                let week = if week == 5 { -1 } else { week as i8 };
+                return Some(format!("FREQ=YEARLY;BYMONTH={month};BYDAY={week}{weekday}"));
+            }
+            // Days of the month the shifted weekday falls on, counted from the end for the last week
+            let first = if week == 5 {
+                shift - 7
+            } else {
+                1 + 7 * (week as isize - 1) + shift
+            };
+            let days = first..first + 7;
+            // Shortest length of the month
+            let length = match month {
+                2 => 28,
+                4 | 6 | 9 | 11 => 30,
+                _ => 31,
+            };
+            let in_month = if week == 5 {
+                days.start >= -length && days.end <= 0
+            } else {
+                days.start >= 1 && days.end <= length + 1
+            };
+            if !in_month {
+                return None;
+            }
+            let days: Vec<String> = days.map(|d| d.to_string()).collect();
+            Some(format!(
+                "FREQ=YEARLY;BYMONTH={month};BYDAY={weekday};BYMONTHDAY={}",
+                days.join(",")
+            ))
+        }
+        // February 29th is never counted: the month and day are the ones of a common year
+        PosixDate::Julian(n) if shift == 0 => {
+            let date = NaiveDate::from_yo_opt(1970, n as u32)?;
+            Some(format!(
+                "FREQ=YEARLY;BYMONTH={};BYMONTHDAY={}",
+                date.month(),
+                date.day()
+            ))
+        }
+        PosixDate::ZeroBased(n) => {
+            let day = n as isize + 1 + shift;
+            (1..=365)
+                .contains(&day)
+                .then(|| format!("FREQ=YEARLY;BYYEARDAY={day}"))
+        }
+        PosixDate::Julian(_) => None,
+    }
+}
+
+// A STANDARD or DAYLIGHT sub-component of an imported VTIMEZONE
+#[derive(Default)]
+struct Component {
+    isdst: bool,
+    offset_from: Option<isize>,
+    offset_to: Option<isize>,
+    abbreviation: Option<String>,
+    dtstart: Option<NaiveDateTime>,
+    rrule: Option<Rrule>,
+    rdates: Vec<NaiveDateTime>,
+}
+
+// Yearly recurrence rule (the only frequency used by VTIMEZONE components)
+#[derive(Default)]
+struct Rrule {
+    interval: i32,
+    months: Vec<u32>,
+    // Ordinal (0 for every occurrence in the month) and weekday (0 is Sunday)
+    weekdays: Vec<(i32, u32)>,
+    monthdays: Vec<i32>,
+    yeardays: Vec<i32>,
+    // UNTIL, and whether it is a UTC time
+    until: Option<(NaiveDateTime, bool)>,
+    count: Option<usize>,
+}
+
+pub(crate) fn from_vtimezone(ics: &str) -> Result<Tz, TzError> {
+    // Unfolds the content lines
+    let mut lines: Vec<String> = Vec::new();
+    for line in ics.lines() {
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(folded), Some(last)) => last.push_str(folded),
+            _ => lines.push(line.to_string()),
+        }
+    }
+    let begin = lines
+        .iter()
+        .position(|l| l.eq_ignore_ascii_case("BEGIN:VTIMEZONE"))
+        .ok_or(TzError::ParseError)?;
+
+    let mut tzid = None;
+    let mut components: Vec<Component> = Vec::new();
+    let mut current: Option<Component> = None;
+    for line in &lines[begin + 1..] {
+        let (name, value) = line.split_once(':').ok_or(TzError::ParseError)?;
+        let name = name
+            .split(';')
+            .next()
+            .unwrap_or_default()
+            .to_ascii_uppercase();
+        let value = value.trim();
+        match (name.as_str(), current.as_mut()) {
+            ("END", None) if value.eq_ignore_ascii_case("VTIMEZONE") => break,
+            ("TZID", None) => tzid = Some(value.to_string()),
+            ("BEGIN", None) => {
+                current = Some(Component {
+                    isdst: match value.to_ascii_uppercase().as_str() {
+                        "DAYLIGHT" => true,
+                        "STANDARD" => false,
+                        _ => return Err(TzError::ParseError),
+                    },
+                    ..Component::default()
+                })
+            }
+            ("END", Some(_)) => components.extend(current.take()),
+            ("TZOFFSETFROM", Some(c)) => c.offset_from = Some(parse_offset(value)?),
+            ("TZOFFSETTO", Some(c)) => c.offset_to = Some(parse_offset(value)?),
+            ("TZNAME", Some(c)) if c.abbreviation.is_none() => {
+                c.abbreviation = Some(value.to_string())
+            }
+            ("DTSTART", Some(c)) => c.dtstart = Some(parse_datetime(value)?.0),
+            ("RRULE", Some(c)) => c.rrule = Some(parse_rrule(value)?),
+            // Periods are given by their start
+            ("RDATE", Some(c)) => {
+                for rdate in value.split(',') {
+                    let start = rdate.split('/').next().unwrap_or_default();
+                    c.rdates.push(parse_datetime(start)?.0);
+                }
+            }
+            _ => {}
+        }
+    }
+    if components.is_empty()
+        || components
+            .iter()
+            .any(|c| c.offset_from.is_none() || c.offset_to.is_none() || c.dtstart.is_none())
+    {
+        return Err(TzError::ParseError);
+    }
+
+    // The rules in force (without UNTIL nor COUNT) become the TZ string footer, if they can be written as one
+    let tz_footer = footer(&components);
+    // Transitions are listed up to the year following the last irregular one, or 2037 (as zic does) without footer
+    let mut horizon = components
+        .iter()
+        .flat_map(|c| {
+            let until = c.rrule.as_ref().and_then(|r| r.until).map(|u| u.0);
+            c.dtstart
+                .into_iter()
+                .chain(c.rdates.iter().copied())
+                .chain(until)
+        })
+        .map(|t| t.year())
+        .max()
+        .unwrap_or_default()
+        + 1;
+    if tz_footer.is_none() {
+        horizon = horizon.max(2037);
+    }
+
+    // Transition times, with the offset to UTC before them and the local time type they lead to
+    let mut transitions: Vec<(i64, isize, (isize, bool, String))> = Vec::new();
+    for c in &components {
+        let (offset_from, offset_to) = (
+            c.offset_from.unwrap_or_default(),
+            c.offset_to.unwrap_or_default(),
+        );
+        let abbreviation = abbreviation(c.abbreviation.as_deref(), offset_to)?;
+        for onset in onsets(c, horizon) {
+            let t = onset.and_utc().timestamp() - offset_from as i64;
+            transitions.push((t, offset_from, (offset_to, c.isdst, abbreviation.clone())));
+        }
+    }
+    transitions.sort_by_key(|&(t, _, _)| t);
+    transitions.dedup_by_key(|&mut (t, _, _)| t);
+
+    // Local time type before the first transition
+    let first_offset = transitions
+        .first()
+        .map_or(0, |&(_, offset_from, _)| offset_from);
+    let initial = transitions
+        .iter()
+        .map(|(_, _, tt)| tt)
+        .find(|tt| tt.0 == first_offset && !tt.1)
+        .cloned()
+        .unwrap_or((first_offset, false, abbreviation(None, first_offset)?));
+
+    let mut types = Vec::from([initial]);
+    let mut tzh_timecnt_data = Vec::new();
+    let mut tzh_timecnt_indices = Vec::new();
+    for (t, _, tt) in transitions {
+        let previous = tzh_timecnt_indices.last().map_or(0, |&i: &u8| i as usize);
+        // Transitions to the same local time type are dropped
+        if types[previous] == tt {
+            continue;
+        }
+        let index = match types.iter().position(|known| *known == tt) {
+            Some(i) => i,
+            None => {
+                types.push(tt);
+                types.len() - 1
+            }
+        };
+        tzh_timecnt_data.push(t);
+        tzh_timecnt_indices.push(u8::try_from(index).map_err(|_| TzError::ParseError)?);
+    }
+
+    let mut tz_abbr: Vec<String> = Vec::new();
+    let mut tzh_typecnt = Vec::new();
+    for (utc_offset, isdst, abbreviation) in types {
+        let abbrind = match tz_abbr.iter().position(|a| *a == abbreviation) {
+            Some(i) => i,
+            None => {
+                tz_abbr.push(abbreviation);
+                tz_abbr.len() - 1
+            }
+        };
+        tzh_typecnt.push(Ttinfo {
+            tt_utoff: utc_offset,
+            tt_isdst: isdst as u8,
+            tt_abbrind: u8::try_from(abbrind).map_err(|_| TzError::ParseError)?,
+        });
+    }
+
+    // Rule times out of 0 to 24 hours are a version 3 extension
+    let extended = tz_footer
+        .as_ref()
+        .and_then(|f| f.dst.as_ref())
+        .is_some_and(|d| {
+            !(0..=86400).contains(&d.start_time) || !(0..=86400).contains(&d.end_time)
+        });
+    Ok(Tz {
+        tzh_version: if extended { 3 } else { 2 },
+        tzh_timecnt_data,
+        tzh_timecnt_indices,
+        tzh_typecnt,
+        tz_abbr,
+        tzh_leapcnt_data: Vec::new(),
+        tz_footer,
+        name: tzid.ok_or(TzError::ParseError)?,
+    })
+}
+
+// Local times (before the transition) of a component's onsets: DTSTART, RDATEs and RRULE occurrences up to a year
+fn onsets(c: &Component, horizon: i32) -> Vec<NaiveDateTime> {
+    let dtstart = match c.dtstart {
+        Some(dtstart) => dtstart,
+        None => return Vec::new(),
+    };
+    let mut onsets = c.rdates.clone();
+    let rule = match &c.rrule {
+        Some(rule) => rule,
+        None => {
+            onsets.push(dtstart);
+            return onsets;
+        }
+    };
+    let offset_from = c.offset_from.unwrap_or_default() as i64;
+    let last_year = match (rule.until, rule.count) {
+        (Some((until, _)), _) => until.year(),
+        // Bounded by the count, a rule which never occurs stops after a few centuries
+        (None, Some(_)) => dtstart.year() + 400,
+        (None, None) => horizon,
+    };
+    let mut count = 0;
+    let mut year = dtstart.year();
+    'years: while year <= last_year {
+        for date in rule.dates(year, dtstart.date()) {
+            let t = date.and_time(dtstart.time());
+            if t < dtstart {
+                continue;
+            }
+            let beyond = match rule.until {
+                Some((until, true)) => t - Duration::seconds(offset_from) > until,
+                Some((until, false)) => t > until,
+                None => false,
+            };
+            count += 1;
+            if beyond || rule.count.is_some_and(|n| count > n) {
+                break 'years;
+            }
+            // A DTSTART not matching the rule (ie. 16010101T000000) is only its start
+            onsets.push(t);
+        }
+        year = match year.checked_add(rule.interval) {
+            Some(year) => year,
+            None => break,
+        };
+    }
+    onsets
+}
+
+impl Rrule {
+    // Dates of the rule in a year, sorted
+    fn dates(&self, year: i32, dtstart: NaiveDate) -> Vec<NaiveDate> {
+        let mut dates = Vec::new();
+        if !self.yeardays.is_empty() {
+            let length = if NaiveDate::from_ymd_opt(year, 2, 29).is_some() {
+                366
+            } else {
+                365
+            };
+            for &day in &self.yeardays {
+                let day = if day < 0 { length + 1 + day } else { day };
+                dates.extend(
+                    u32::try_from(day)
+                        .ok()
+                        .and_then(|d| NaiveDate::from_yo_opt(year, d)),
+                );
+            }
+        } else {
+            let months = if self.months.is_empty() {
+                Vec::from([dtstart.month()])
+            } else {
+                self.months.clone()
+            };
+            for month in months {
+                let days: Vec<NaiveDate> = (1..=31)
+                    .filter_map(|d| NaiveDate::from_ymd_opt(year, month, d))
+                    .collect();
+                let mut candidates: Vec<NaiveDate> = if !self.weekdays.is_empty() {
+                    self.weekdays
+                        .iter()
+                        .flat_map(|&(n, weekday)| {
+                            let matching: Vec<NaiveDate> = days
+                                .iter()
+                                .filter(|d| d.weekday().num_days_from_sunday() == weekday)
+                                .copied()
+                                .collect();
+                            match n {
+                                0 => matching,
+                                n if n > 0 => {
+                                    matching.get(n as usize - 1).copied().into_iter().collect()
+                                }
+                                n => matching
+                                    .len()
+                                    .checked_sub(n.unsigned_abs() as usize)
+                                    .and_then(|i| matching.get(i).copied())
+                                    .into_iter()
+                                    .collect(),
+                            }
+                        })
+                        .collect()
+                } else if !self.monthdays.is_empty() {
+                    days.clone()
+                } else {
+                    days.iter()
+                        .copied()
+                        .filter(|d| d.day() == dtstart.day())
+                        .collect()
+                };
+                if !self.monthdays.is_empty() {
+                    let length = days.len() as i32;
+                    candidates.retain(|d| {
+                        let day = d.day() as i32;
+                        self.monthdays
+                            .iter()
+                            .any(|&md| md == day || md == day - length - 1)
+                    });
+                }
+                dates.extend(candidates);
+            }
+        }
+        dates.sort_unstable();
+        dates.dedup();
+        dates
+    }
+
+    fn is_infinite(&self) -> bool {
+        self.until.is_none() && self.count.is_none()
+    }
+}
+
+// Footer rule from the DAYLIGHT and STANDARD rules in force
+fn footer(components: &[Component]) -> Option<PosixTz> {
+    let in_force: Vec<&Component> = components
+        .iter()
+        .filter(|c| c.rrule.as_ref().is_some_and(Rrule::is_infinite))
+        .collect();
+    let (dst, std) = match in_force[..] {
+        [a, b] if a.isdst && !b.isdst => (a, b),
+        [a, b] if b.isdst && !a.isdst => (b, a),
+        _ => return None,
+    };
+    let (std_utoff, dst_utoff) = (std.offset_to?, dst.offset_to?);
+    // The rule times are local times of the other part of the rule
+    if dst.offset_from? != std_utoff || std.offset_from? != dst_utoff {
+        return None;
+    }
+    let (start, start_time) = posix_date(dst)?;
+    let (end, end_time) = posix_date(std)?;
+    Some(PosixTz::new(
+        abbreviation(std.abbreviation.as_deref(), std_utoff).ok()?,
+        std_utoff,
+        Some(PosixDst {
+            abbr: abbreviation(dst.abbreviation.as_deref(), dst_utoff).ok()?,
+            utoff: dst_utoff,
+            start,
+            start_time,
+            end,
+            end_time,
+        }),
+    ))
+}
+
+// TZ string date and time of a component's RRULE (the reverse of rrule())
+fn posix_date(c: &Component) -> Option<(PosixDate, isize)> {
+    let rule = c.rrule.as_ref()?;
+    if rule.interval != 1 {
+        return None;
+    }
+    let time = c.dtstart?.time().num_seconds_from_midnight() as isize;
+    match (
+        &rule.months[..],
+        &rule.weekdays[..],
+        &rule.monthdays[..],
+        &rule.yeardays[..],
+    ) {
+        (&[month], &[(n, weekday)], [], []) if (1..=4).contains(&n) || n == -1 => Some((
+            PosixDate::MonthWeekDay {
+                month: month as u8,
+                week: if n == -1 { 5 } else { n as u8 },
+                weekday: weekday as u8,
+            },
+            time,
+        )),
+        // Weekday within 7 consecutive days of the month: a weekday shifted by a number of days
+        (&[month], &[(0, weekday)], days, []) if days.len() == 7 => {
+            let first = *days.iter().min()?;
+            if days.iter().any(|&d| !(first..first + 7).contains(&d))
+                || (first < 0 && first + 6 >= 0)
+            {
+                return None;
+            }
+            let (week, shift) = if first > 0 {
+                ((first - 1) / 7 + 1, (first - 1) % 7)
+            } else {
+                (5, first + 7)
+            };
+            if week > 5 || (first > 0 && week > 4) {
+                return None;
+            }
+            Some((
+                PosixDate::MonthWeekDay {
+                    month: month as u8,
+                    week: week as u8,
+                    weekday: (weekday as i32 - shift).rem_euclid(7) as u8,
+                },
+                time + shift as isize * 86400,
+            ))
+        }
+        // February 29th is never counted
+        (&[month], [], &[day], []) if day > 0 => {
+            let date = NaiveDate::from_ymd_opt(1970, month, day as u32)?;
+            Some((PosixDate::Julian(date.ordinal() as u16), time))
+        }
+        ([], [], [], &[day]) if day > 0 => Some((PosixDate::ZeroBased(day as u16 - 1), time)),
+        _ => None,
+    }
+}
+
+// TZNAME, if it can be used in a TZ string, otherwise a numeric abbreviation (ie. +01, -0330)
+fn abbreviation(tzname: Option<&str>, utc_offset: isize) -> Result<String, TzError> {
+    match tzname {
+        Some(name)
+            if name.len() >= 3
+                && name
+                    .bytes()
+                    .all(|c| c.is_ascii_alphanumeric() || c == b'+' || c == b'-') =>
+        {
+            Ok(name.to_string())
+        }
+        _ => {
+            let utc = Utc.timestamp_opt(0, 0).single().ok_or(TzError::NoData)?;
+            let offset = FixedOffset::east_opt(utc_offset as i32).ok_or(TzError::ParseError)?;
+            Ok(format::strftime(&utc.with_timezone(&offset), "", "%:::z")?.replace(':', ""))
+        }
+    }
+}
+
+// +hhmm or +hhmmss
+fn parse_offset(s: &str) -> Result<isize, TzError> {
+    let (sign, hms) = match s.split_at_checked(1) {
+        Some(("+", hms)) => (1, hms),
+        Some(("-", hms)) => (-1, hms),
+        _ => return Err(TzError::ParseError),
+    };
+    if !(hms.len() == 4 || hms.len() == 6) || !hms.bytes().all(|c| c.is_ascii_digit()) {
+        return Err(TzError::ParseError);
+    }
+    let mut seconds = 0;
+    for (i, unit) in [3600, 60, 1].iter().enumerate() {
+        seconds += hms
+            .get(2 * i..2 * i + 2)
+            .map_or(Ok(0), str::parse::<isize>)?
+            * unit;
+    }
+    Ok(sign * seconds)
+}
+
+// Date and time (a date being at midnight), and whether it is a UTC time
+fn parse_datetime(s: &str) -> Result<(NaiveDateTime, bool), TzError> {
+    let (s, utc) = match s.strip_suffix(['Z', 'z']) {
+        Some(s) => (s, true),
+        None => (s, false),
+    };
+    let t = NaiveDateTime::parse_from_str(s, "%Y%m%dT%H%M%S")
+        .or_else(|_| NaiveDate::parse_from_str(s, "%Y%m%d").map(|d| d.and_time(NaiveTime::MIN)))
+        .map_err(|_| TzError::ParseError)?;
+    Ok((t, utc))
+}
+
+fn parse_rrule(s: &str) -> Result<Rrule, TzError> {
+    let mut rule = Rrule {
+        interval: 1,
+        ..Rrule::default()
+    };
+    let list = |v: &str| -> Result<Vec<i32>, TzError> {
+        v.split(',')
+            .map(|n| n.trim_start_matches('+').parse().map_err(TzError::from))
+            .collect()
+    };
+    for part in s.split(';') {
+        let (key, value) = part.split_once('=').ok_or(TzError::ParseError)?;
+        match key.to_ascii_uppercase().as_str() {
+            "FREQ" if value.eq_ignore_ascii_case("YEARLY") => {}
+            "INTERVAL" => {
+                rule.interval = match value.parse::<i32>()? {
+                    interval @ 1..=MAX_INTERVAL => interval,
+                    _ => return Err(TzError::ParseError),
+                }
+            }
+            "BYMONTH" => {
+                rule.months = list(value)?
+                    .into_iter()
+                    .map(|m| u32::try_from(m).map_err(|_| TzError::ParseError))
+                    .collect::<Result<_, _>>()?
+            }
+            "BYMONTHDAY" => rule.monthdays = list(value)?,
+            "BYYEARDAY" => rule.yeardays = list(value)?,
+            "BYDAY" => {
+                for day in value.split(',') {
+                    let (n, weekday) = day
+                        .split_at_checked(day.len().saturating_sub(2))
+                        .ok_or(TzError::ParseError)?;
+                    let weekday = WEEKDAYS
+                        .iter()
+                        .position(|w| w.eq_ignore_ascii_case(weekday))
+                        .ok_or(TzError::ParseError)?;
+                    let n = if n.is_empty() {
+                        0
+                    } else {
+                        n.trim_start_matches('+').parse()?
+                    };
+                    rule.weekdays.push((n, weekday as u32));
+                }
+            }
+            "UNTIL" => rule.until = Some(parse_datetime(value)?),
+            "COUNT" => rule.count = Some(value.parse()?),
+            "WKST" => {}
+            // Other frequencies and rule parts are not used by VTIMEZONE components
+            _ => return Err(TzError::ParseError),
+        }
+    }
+    Ok(rule)
+}
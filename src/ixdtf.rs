@@ -0,0 +1,256 @@
+//! RFC 9557 Internet Extended Date/Time Format, ie. ```2025-06-01T12:00:00+02:00[Europe/Paris]```
+//! (available with the parse or json features).
+//!
+//! The time zone annotation may be marked critical (```[!Europe/Paris]```): an offset inconsistent with the zone
+//! is then an error. Without the critical flag, the offset prevails and the zone is ignored.
+//! Other annotations (ie. ```[u-ca=iso8601]```) are ignored, unless marked critical.
+
+use crate::parser::zoned;
+use crate::{format, Disambiguation, String, ToString, Tz, TzError, ZoneLoader, ZonedDateTime};
+use chrono::{DateTime, FixedOffset, NaiveDateTime, Utc};
+
+// Date and time layouts of RFC 3339, the seconds being optional as in Temporal
+const LAYOUTS: [&str; 2] = ["%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%dT%H:%M"];
+
+// Calendars equivalent to the ISO 8601 calendar
+const CALENDARS: [&str; 2] = ["iso8601", "gregory"];
+
+enum Offset {
+    // Z or -00:00: the instant is known, the local offset is not
+    Unknown,
+    // Offset to UTC in seconds, and whether the seconds were given
+    Numeric(i32, bool),
+}
+
+enum Zone<'a> {
+    Name(&'a str),
+    Offset(i32),
+}
+
+struct Ixdtf<'a> {
+    local: NaiveDateTime,
+    offset: Option<Offset>,
+    zone: Option<Zone<'a>>,
+    critical: bool,
+}
+
+impl ZoneLoader {
+    /// Parses an RFC 9557 date and time, ie. ```2025-06-01T12:00:00+02:00[Europe/Paris]```.
+    ///
+    /// The zone is loaded to check the consistency of the offset: an inconsistent offset is an error if the zone is marked
+    /// critical (```[!Europe/Paris]```), otherwise the offset prevails and the zone is ignored.
+    /// Without an offset, the local time is converted to UTC according to the disambiguation policy.
+    ///
+    /// ```rust
+    /// use libtzfile::{Disambiguation, ZoneLoader};
+    /// let loader = ZoneLoader::default();
+    /// let parsed = loader
+    ///     .parse_ixdtf("2025-06-01T12:00:00+02:00[!Europe/Paris]", Disambiguation::Reject)
+    ///     .unwrap();
+    /// println!("{} {}", parsed.utc, parsed.abbreviation);
+    /// println!("{:?}", loader.parse_ixdtf("2025-06-01T12:00:00+01:00[!Europe/Paris]", Disambiguation::Reject));
+    /// ```
+    ///
+    /// ```text
+    /// 2025-06-01 10:00:00 UTC CEST
+    /// Err(InconsistentOffset)
+    /// ```
+    pub fn parse_ixdtf(
+        &self,
+        s: &str,
+        disambiguation: Disambiguation,
+    ) -> Result<ZonedDateTime, TzError> {
+        let ixdtf = parse(s)?;
+        let local = ixdtf.local;
+        let utc_at = |offset: i32| local.and_utc() - chrono::Duration::seconds(offset as i64);
+
+        match (ixdtf.zone, ixdtf.offset) {
+            (Some(Zone::Name(name)), offset) => {
+                let tz = self.load(name)?;
+                let utc = match offset {
+                    Some(Offset::Unknown) => local.and_utc(),
+                    Some(Offset::Numeric(offset, precise)) => {
+                        match consistent_offset(&tz, utc_at(offset), offset, precise) {
+                            Some(utc_offset) => utc_at(utc_offset),
+                            None if ixdtf.critical => return Err(TzError::InconsistentOffset),
+                            None => return fixed(utc_at(offset), offset),
+                        }
+                    }
+                    None => tz.local_to_utc(local, disambiguation)?,
+                };
+                let ltt = tz.lookup(utc);
+                zoned(
+                    utc,
+                    ltt.utc_offset,
+                    Some(name.to_string()),
+                    ltt.abbreviation,
+                )
+            }
+            (Some(Zone::Offset(zone)), Some(Offset::Numeric(offset, _))) if offset != zone => {
+                if ixdtf.critical {
+                    Err(TzError::InconsistentOffset)
+                } else {
+                    fixed(utc_at(offset), offset)
+                }
+            }
+            (Some(Zone::Offset(zone)), Some(Offset::Unknown)) => fixed(local.and_utc(), zone),
+            (Some(Zone::Offset(offset)), _) | (None, Some(Offset::Numeric(offset, _))) => {
+                fixed(utc_at(offset), offset)
+            }
+            (None, Some(Offset::Unknown)) => zoned(local.and_utc(), 0, None, String::from("UTC")),
+            // A local time without offset nor zone is not an instant
+            (None, None) => Err(TzError::ParseError),
+        }
+    }
+}
+
+pub(crate) fn format_ixdtf(
+    tz: &Tz,
+    instant: DateTime<Utc>,
+    critical: bool,
+) -> Result<String, TzError> {
+    let (utc_offset, _, _) = tz.local_type_at(instant.timestamp());
+    let offset = FixedOffset::east_opt(utc_offset as i32).ok_or(TzError::InvalidTimezone)?;
+    let mut formatted = instant
+        .with_timezone(&offset)
+        .format("%Y-%m-%dT%H:%M:%S%.f")
+        .to_string();
+    // RFC 3339 offsets have no seconds: LMT offsets are rounded to the minute, as Temporal does
+    let minutes = (utc_offset.abs() + 30) / 60;
+    formatted.push(if utc_offset < 0 { '-' } else { '+' });
+    formatted.push_str(&std::format!("{:02}:{:02}", minutes / 60, minutes % 60));
+    if !tz.name.is_empty() {
+        formatted.push('[');
+        if critical {
+            formatted.push('!');
+        }
+        formatted.push_str(&tz.name);
+        formatted.push(']');
+    }
+    Ok(formatted)
+}
+
+// Offset of the zone at an instant, if consistent with the given one. An offset without seconds is consistent
+// with a zone offset having seconds (ie. LMT) when equal to it rounded to the minute.
+fn consistent_offset(tz: &Tz, utc: DateTime<Utc>, offset: i32, precise: bool) -> Option<i32> {
+    let utc_offset = tz.lookup(utc).utc_offset as i32;
+    let rounded = (utc_offset.abs() + 30) / 60 * 60 * utc_offset.signum();
+    if utc_offset == offset || (!precise && rounded == offset) {
+        Some(utc_offset)
+    } else {
+        None
+    }
+}
+
+// Date and time with a fixed offset, its abbreviation following the tzdata numeric style (ie. +02, -0330)
+fn fixed(utc: DateTime<Utc>, offset: i32) -> Result<ZonedDateTime, TzError> {
+    let local = utc.with_timezone(&FixedOffset::east_opt(offset).ok_or(TzError::ParseError)?);
+    let abbreviation = format::strftime(&local, "", "%:::z")?.replace(':', "");
+    zoned(utc, offset as isize, None, abbreviation)
+}
+
+fn parse(s: &str) -> Result<Ixdtf<'_>, TzError> {
+    // Date and time, up to the offset or the first annotation
+    let end = s
+        .char_indices()
+        .skip(10)
+        .find(|&(_, c)| matches!(c, 'Z' | 'z' | '+' | '-' | '['))
+        .map_or(s.len(), |(i, _)| i);
+    let datetime = s[..end].replacen(['t', ' '], "T", 1);
+    let local = LAYOUTS
+        .iter()
+        .find_map(|layout| NaiveDateTime::parse_from_str(&datetime, layout).ok())
+        .ok_or(TzError::ParseError)?;
+
+    let mut rest = &s[end..];
+    let offset = match rest.find('[').unwrap_or(rest.len()) {
+        0 => None,
+        i => {
+            let offset = parse_offset(&rest[..i])?;
+            rest = &rest[i..];
+            Some(offset)
+        }
+    };
+
+    let mut ixdtf = Ixdtf {
+        local,
+        offset,
+        zone: None,
+        critical: false,
+    };
+    let mut first = true;
+    while !rest.is_empty() {
+        let close = rest.find(']').ok_or(TzError::ParseError)?;
+        if !rest.starts_with('[') {
+            return Err(TzError::ParseError);
+        }
+        let (critical, annotation) = match rest[1..close].strip_prefix('!') {
+            Some(annotation) => (true, annotation),
+            None => (false, &rest[1..close]),
+        };
+        rest = &rest[close + 1..];
+        match annotation.split_once('=') {
+            // The time zone annotation comes first
+            None if first => {
+                ixdtf.zone = Some(parse_zone(annotation)?);
+                ixdtf.critical = critical;
+            }
+            None => return Err(TzError::ParseError),
+            Some((key, value)) => {
+                if key.is_empty()
+                    || !key.chars().all(|c| {
+                        c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_'
+                    })
+                    || value.is_empty()
+                {
+                    return Err(TzError::ParseError);
+                }
+                if critical && !(key == "u-ca" && CALENDARS.contains(&value)) {
+                    return Err(TzError::UnsupportedAnnotation);
+                }
+            }
+        }
+        first = false;
+    }
+    Ok(ixdtf)
+}
+
+fn parse_offset(s: &str) -> Result<Offset, TzError> {
+    if s == "Z" || s == "z" || s == "-00:00" {
+        return Ok(Offset::Unknown);
+    }
+    let (sign, hms) = match s.split_at_checked(1) {
+        Some(("+", hms)) => (1, hms),
+        Some(("-", hms)) => (-1, hms),
+        _ => return Err(TzError::ParseError),
+    };
+    let (seconds, precise) = parse_hms(hms)?;
+    Ok(Offset::Numeric(sign * seconds, precise))
+}
+
+fn parse_zone(s: &str) -> Result<Zone<'_>, TzError> {
+    match parse_offset(s) {
+        Ok(Offset::Numeric(offset, false)) => Ok(Zone::Offset(offset)),
+        _ if s.starts_with(['+', '-']) => Err(TzError::ParseError),
+        _ if s.is_empty() => Err(TzError::ParseError),
+        _ => Ok(Zone::Name(s)),
+    }
+}
+
+// hh:mm or hh:mm:ss, returning the number of seconds and whether they were given
+fn parse_hms(s: &str) -> Result<(i32, bool), TzError> {
+    let fields: crate::Vec<&str> = s.split(':').collect();
+    if !(2..=3).contains(&fields.len())
+        || fields
+            .iter()
+            .any(|f| f.len() != 2 || !f.bytes().all(|b| b.is_ascii_digit()))
+    {
+        return Err(TzError::ParseError);
+    }
+    let (h, m): (i32, i32) = (fields[0].parse()?, fields[1].parse()?);
+    let s: i32 = fields.get(2).map_or(Ok(0), |s| s.parse())?;
+    if h > 23 || m > 59 || s > 59 {
+        return Err(TzError::ParseError);
+    }
+    Ok((h * 3600 + m * 60 + s, fields.len() == 3))
+}
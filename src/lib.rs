@@ -74,6 +74,24 @@
 //!
 //! This feature is used in my [world time API](https://crates.io/crates/world-time-api).
 //!
+//! The **wasm** feature (which includes the parse feature) exports the Tz struct's parsing, lookup, conversion and transitions
+//! methods to JavaScript with wasm-bindgen, so that TZfiles can be used in a browser. To build the module:
+//!```text
+//! cargo rustc --lib --release --target wasm32-unknown-unknown --features wasm --crate-type cdylib
+//! wasm-bindgen --target web --out-dir pkg target/wasm32-unknown-unknown/release/libtzfile.wasm
+//!```
+//!
+//! The **capi** feature (which includes the parse feature) exports a C ABI: opaque ```Tz``` handles loaded from bytes or a path,
+//! lookups, conversions, and integer error codes with their messages. The header is ```include/libtzfile.h```, generated by cbindgen,
+//! and ```make -C capi test``` builds the static library and runs the C harness:
+//!```text
+//! cargo rustc --lib --release --features capi --crate-type staticlib
+//!```
+//!
+//! The **python** feature (which includes the parse feature) is a PyO3 extension module: the ```libtzfile``` Python module
+//! exposes the Tz class (transition_times, zoneinfo, lookup and conversions) and ZoneInfo, a ```datetime.tzinfo``` subclass
+//! giving the same results as the standard zoneinfo module. It is built with maturin (```maturin develop```).
+//!
 //! The tests (`cargo test`, ```cargo test --no-default-features``` or ```cargo test --features parse|json```) are working with the [2025a timezone database](https://data.iana.org/time-zones/tz-link.html).
 
 // Support using libtzfile without the standard library
@@ -81,6 +99,7 @@
 
 #[cfg(any(feature = "std", feature = "parse", feature = "json"))]
 #[cfg(test)]
+#[allow(clippy::bool_assert_comparison, clippy::useless_vec)]
 mod tests;
 #[cfg(any(feature = "std", feature = "parse", feature = "json"))]
 extern crate std;
@@ -98,14 +117,14 @@ extern crate alloc;
 use alloc::{str::from_utf8, string::String, string::ToString, vec::Vec};
 
 #[cfg(any(feature = "parse", feature = "json"))]
-use chrono::{DateTime, FixedOffset, TimeZone, Utc};
+use chrono::{DateTime, Datelike, FixedOffset, NaiveDateTime, TimeZone, Utc};
 #[cfg(feature = "json")]
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 #[cfg(feature = "json")]
 mod offset_serializer {
     use serde::Serialize;
-    use std::{format, string::String};
+    use std::{format, string::String, vec::Vec};
     fn offset_to_json(t: chrono::FixedOffset) -> String {
         format!("{:?}", t)
     }
@@ -116,16 +135,102 @@ mod offset_serializer {
     ) -> Result<S::Ok, S::Error> {
         offset_to_json(time.clone()).serialize(serializer)
     }
+
+    // +HH:MM, or +HH:MM:SS for offsets with seconds
+    pub fn deserialize<'de, D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<chrono::FixedOffset, D::Error> {
+        use serde::{de::Error, Deserialize};
+        let s = String::deserialize(deserializer)?;
+        let invalid = || D::Error::custom(format!("invalid offset: {}", s));
+        let (sign, hms) = match s.split_at_checked(1) {
+            Some(("+", hms)) => (1, hms),
+            Some(("-", hms)) => (-1, hms),
+            _ => return Err(invalid()),
+        };
+        let fields: Vec<&str> = hms.split(':').collect();
+        if !(2..=3).contains(&fields.len()) || fields.iter().any(|f| f.len() != 2) {
+            return Err(invalid());
+        }
+        let mut seconds = 0;
+        for field in fields {
+            seconds = seconds * 60 + field.parse::<i32>().map_err(|_| invalid())?;
+        }
+        chrono::FixedOffset::east_opt(sign * seconds).ok_or_else(invalid)
+    }
 }
 
 use byteorder::{ByteOrder, BE};
 
+mod posix;
+pub use posix::{PosixDate, PosixDst, PosixTz};
+#[cfg(feature = "capi")]
+pub mod capi;
+#[cfg(any(feature = "parse", feature = "json"))]
+mod eras;
+#[cfg(any(feature = "parse", feature = "json"))]
+pub use eras::Era;
+#[cfg(any(feature = "parse", feature = "json"))]
+mod format;
+#[cfg(feature = "json")]
+mod history;
+#[cfg(feature = "json")]
+pub use history::ZoneHistory;
+#[cfg(any(feature = "parse", feature = "json"))]
+mod ical;
+#[cfg(any(feature = "parse", feature = "json"))]
+mod ixdtf;
+#[cfg(any(feature = "parse", feature = "json"))]
+mod loader;
+#[cfg(any(feature = "parse", feature = "json"))]
+pub use loader::ZoneLoader;
+#[cfg(any(feature = "parse", feature = "json"))]
+mod local;
+#[cfg(any(feature = "parse", feature = "json"))]
+pub use local::{Disambiguation, LocalMapping, LocalTimeType};
+#[cfg(any(feature = "parse", feature = "json"))]
+mod names;
+#[cfg(any(feature = "parse", feature = "json"))]
+pub use names::{MetaZonePeriod, MetaZones, NameLength, TimeZoneNames};
+#[cfg(any(feature = "parse", feature = "json"))]
+mod parser;
+#[cfg(any(feature = "parse", feature = "json"))]
+pub use parser::ZonedDateTime;
+#[cfg(feature = "python")]
+mod python;
+#[cfg(feature = "python")]
+pub use python::{PyTz, PyZoneInfo};
+#[cfg(any(feature = "parse", feature = "json"))]
+mod transitions;
+#[cfg(any(feature = "parse", feature = "json"))]
+pub use transitions::{Transition, Transitions, TransitionsBefore};
+#[cfg(any(feature = "parse", feature = "json"))]
+mod windows;
+#[cfg(any(feature = "parse", feature = "json"))]
+pub use windows::{MapZone, WindowsZones};
+#[cfg(feature = "wasm")]
+mod wasm;
+#[cfg(feature = "wasm")]
+pub use wasm::{JsLocalTimeType, JsTransition, JsTransitions, JsTz};
+#[cfg(feature = "json")]
+mod worldtimeapi;
+#[cfg(feature = "json")]
+pub use worldtimeapi::WorldTimeApi;
+#[cfg(any(feature = "parse", feature = "json"))]
+mod xml;
+#[cfg(any(feature = "parse", feature = "json"))]
+mod zic;
+
 // TZif magic four bytes
 const MAGIC: u32 = 0x545A6966;
 // Header length
 const HEADER_LEN: usize = 0x2C;
+// "Big bang" transition time (-2^59) written by zic in some TZfiles, out of chrono's range
+#[cfg(any(feature = "parse", feature = "json"))]
+const BIG_BANG: i64 = -576460752303423488;
 
 #[derive(Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
 pub enum TzError {
     // Invalid timezone
     InvalidTimezone,
@@ -133,7 +238,7 @@ pub enum TzError {
     InvalidMagic,
     // Bad utf8 string
     BadUtf8String,
-    // Only V2 format is supported
+    // Only V2 (and later) format is supported
     UnsupportedFormat,
     // No data matched the request
     NoData,
@@ -143,6 +248,18 @@ pub enum TzError {
     EmptyString,
     // Json conversion error
     JsonError,
+    // Invalid format string
+    InvalidFormat,
+    // Local time occurring twice
+    AmbiguousLocalTime,
+    // Local time skipped by a transition
+    SkippedLocalTime,
+    // Abbreviation used by zones with different offsets
+    AmbiguousAbbreviation,
+    // Offset inconsistent with the timezone
+    InconsistentOffset,
+    // Unsupported critical annotation
+    UnsupportedAnnotation,
 }
 
 #[cfg(any(feature = "std", feature = "parse", feature = "json"))]
@@ -153,11 +270,17 @@ impl fmt::Display for TzError {
             TzError::InvalidTimezone => "Invalid timezone",
             TzError::InvalidMagic => "Invalid TZfile",
             TzError::BadUtf8String => "Bad utf8 string",
-            TzError::UnsupportedFormat => "Only V2 format is supported",
+            TzError::UnsupportedFormat => "Only V2 (and later) format is supported",
             TzError::NoData => "No data matched the request",
             TzError::ParseError => "Parsing error",
             TzError::EmptyString => "Empty string",
             TzError::JsonError => "Could not convert to json",
+            TzError::InvalidFormat => "Invalid format string",
+            TzError::AmbiguousLocalTime => "Ambiguous local time",
+            TzError::SkippedLocalTime => "Local time skipped by a transition",
+            TzError::AmbiguousAbbreviation => "Ambiguous timezone abbreviation",
+            TzError::InconsistentOffset => "Offset inconsistent with the timezone",
+            TzError::UnsupportedAnnotation => "Unsupported critical annotation",
         })
     }
 }
@@ -186,7 +309,7 @@ impl From<std::str::Utf8Error> for TzError {
 #[cfg(any(feature = "std", feature = "parse", feature = "json"))]
 impl From<TzError> for std::io::Error {
     fn from(e: TzError) -> std::io::Error {
-        std::io::Error::new(std::io::ErrorKind::Other, e)
+        std::io::Error::other(e)
     }
 }
 
@@ -201,28 +324,82 @@ impl From<serde_json::error::Error> for TzError {
 impl error::Error for TzError {}
 
 /// This is the crate's primary structure, which contains the TZfile fields.
-#[derive(Debug)]
+/// With the json feature enabled, it implements the Serialize and Deserialize traits. Deserialization checks the tables
+/// as the TZfile parser does.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "json", serde(try_from = "RawTz"))]
 pub struct Tz {
+    /// format version (2, 3 or 4)
+    pub tzh_version: u8,
     /// transition times timestamps table
     pub tzh_timecnt_data: Vec<i64>,
     /// indices for the next field
     pub tzh_timecnt_indices: Vec<u8>,
     /// a struct containing UTC offset, daylight saving time, abbreviation index
     pub tzh_typecnt: Vec<Ttinfo>,
-    /// abbreviations table
+    /// abbreviations table: the abbreviations of the TZfile, in its order, followed by the ones found inside another
+    /// (ie. HST at the end of AHST), tt_abbrind being an index in it
     pub tz_abbr: Vec<String>,
+    /// leap second records (only found in the "right" TZfiles)
+    pub tzh_leapcnt_data: Vec<LeapSecond>,
+    /// TZ string footer, which gives the zone's rule after the last transition time
+    pub tz_footer: Option<PosixTz>,
     #[cfg(any(feature = "parse", feature = "json"))]
     name: String,
 }
 
+// Deserialized Tz fields, before the checks
+#[cfg(feature = "json")]
+#[derive(Deserialize)]
+struct RawTz {
+    tzh_version: u8,
+    tzh_timecnt_data: Vec<i64>,
+    tzh_timecnt_indices: Vec<u8>,
+    tzh_typecnt: Vec<Ttinfo>,
+    tz_abbr: Vec<String>,
+    tzh_leapcnt_data: Vec<LeapSecond>,
+    tz_footer: Option<PosixTz>,
+    name: String,
+}
+
+#[cfg(feature = "json")]
+impl TryFrom<RawTz> for Tz {
+    type Error = TzError;
+
+    fn try_from(raw: RawTz) -> Result<Tz, TzError> {
+        Tz {
+            tzh_version: raw.tzh_version,
+            tzh_timecnt_data: raw.tzh_timecnt_data,
+            tzh_timecnt_indices: raw.tzh_timecnt_indices,
+            tzh_typecnt: raw.tzh_typecnt,
+            tz_abbr: raw.tz_abbr,
+            tzh_leapcnt_data: raw.tzh_leapcnt_data,
+            tz_footer: raw.tz_footer,
+            name: raw.name,
+        }
+        .checked()
+    }
+}
+
 /// This sub-structure of the Tz struct is part of the TZfile format specifications, and contains UTC offset, daylight saving time, abbreviation index.
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
 pub struct Ttinfo {
     pub tt_utoff: isize,
     pub tt_isdst: u8,
     pub tt_abbrind: u8,
 }
 
+/// This sub-structure of the Tz struct is part of the TZfile format specifications, and contains a leap second record:
+/// the time at which it occurs, and the total correction (in seconds) after it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
+pub struct LeapSecond {
+    pub ls_trans: i64,
+    pub ls_corr: i32,
+}
+
 #[derive(Debug, PartialEq)]
 struct Header {
     tzh_ttisutcnt: usize,
@@ -237,6 +414,11 @@ struct Header {
 #[cfg(any(feature = "parse", feature = "json"))]
 /// The TransitionTime struct (available with the parse or json features) contains one transition time.
 #[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
+#[cfg_attr(
+    feature = "python",
+    pyo3::pyclass(module = "libtzfile", get_all, frozen, skip_from_py_object)
+)]
 pub struct TransitionTime {
     /// The UTC time and date of the transition time, BEFORE new parameters apply
     pub time: DateTime<Utc>,
@@ -248,43 +430,81 @@ pub struct TransitionTime {
     pub abbreviation: String,
 }
 
+/// Source of the current date and time for the methods depending on it (available with the parse or json features).
+/// SystemClock is used by the convenience methods, and a ```DateTime<Utc>``` can be used as a fixed clock.
+#[cfg(any(feature = "parse", feature = "json"))]
+pub trait Clock {
+    /// Current UTC date and time
+    fn now(&self) -> DateTime<Utc>;
+}
+
+/// The system clock (```Utc::now()```).
+#[cfg(any(feature = "parse", feature = "json"))]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+#[cfg(any(feature = "parse", feature = "json"))]
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+#[cfg(any(feature = "parse", feature = "json"))]
+impl Clock for DateTime<Utc> {
+    fn now(&self) -> DateTime<Utc> {
+        *self
+    }
+}
+
+/// Years selected by the transition_times method (available with the parse or json features).
+///
+/// For compatibility, it converts from ```Option<i32>```: None selects all years, and Some(0) the current year.
+#[cfg(any(feature = "parse", feature = "json"))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum YearSelection {
+    /// All years recorded in the TZfile
+    All,
+    /// Current year, according to the clock
+    Current,
+    /// A given year
+    Year(i32),
+}
+
+#[cfg(any(feature = "parse", feature = "json"))]
+impl From<Option<i32>> for YearSelection {
+    fn from(y: Option<i32>) -> YearSelection {
+        match y {
+            None => YearSelection::All,
+            Some(0) => YearSelection::Current,
+            Some(y) => YearSelection::Year(y),
+        }
+    }
+}
+
+#[cfg(any(feature = "parse", feature = "json"))]
+impl From<i32> for YearSelection {
+    fn from(y: i32) -> YearSelection {
+        YearSelection::Year(y)
+    }
+}
+
 /// Convenient and human-readable informations about a timezone (available with the parse or json features).
-/// With the json feature enabled, the Tzinfo struct implements the Serialize trait.
+/// With the json feature enabled, the Tzinfo struct implements the Serialize and Deserialize traits.
 ///
 /// Some explanations about the offset fields:
 /// - raw_offset : the "normal" offset to utc, in seconds
 /// - dst_offset : the offset to utc during daylight saving time, in seconds
 /// - utc_offset : the current offset to utc, taking into account daylight saving time or not (according to dst_from and dst_until), in +/- HH:MM
-#[cfg(feature = "json")]
-#[derive(Debug, Serialize)]
-pub struct Tzinfo {
-    /// Timezone name
-    pub timezone: String,
-    /// UTC time
-    pub utc_datetime: DateTime<Utc>,
-    /// Local time
-    pub datetime: DateTime<FixedOffset>,
-    /// Start of DST period
-    pub dst_from: Option<DateTime<Utc>>,
-    /// End of DST period
-    pub dst_until: Option<DateTime<Utc>>,
-    /// Are we in DST period ?
-    pub dst_period: bool,
-    /// Normal offset to UTC, in seconds
-    pub raw_offset: isize,
-    /// DST offset to UTC, in seconds
-    pub dst_offset: isize,
-    /// current offset to UTC, in +/-HH:MM
-    #[serde(with = "offset_serializer")]
-    pub utc_offset: FixedOffset,
-    /// Timezone abbreviation
-    pub abbreviation: String,
-    /// Week number
-    pub week_number: i32,
-}
-
-#[cfg(feature = "parse")]
+///
+/// With the json feature, the ```worldtimeapi``` method gives the same informations in the worldtimeapi.org response schema.
+#[cfg(any(feature = "parse", feature = "json"))]
 #[derive(Debug)]
+#[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
+#[cfg_attr(
+    feature = "python",
+    pyo3::pyclass(module = "libtzfile", get_all, frozen, skip_from_py_object)
+)]
 pub struct Tzinfo {
     /// Timezone name
     pub timezone: String,
@@ -303,6 +523,7 @@ pub struct Tzinfo {
     /// DST offset to UTC, in seconds
     pub dst_offset: isize,
     /// current offset to UTC, in +/-HH:MM
+    #[cfg_attr(feature = "json", serde(with = "offset_serializer"))]
     pub utc_offset: FixedOffset,
     /// Timezone abbreviation
     pub abbreviation: String,
@@ -330,6 +551,26 @@ impl Tzinfo {
     pub fn to_json(&self) -> Result<String, serde_json::error::Error> {
         serde_json::to_string(self)
     }
+
+    /// Returns the Tzinfo in the worldtimeapi.org response schema, for clients of that API: same fields and formats,
+    /// ```dst_offset``` being the DST amount in force, and ```dst_from``` and ```dst_until``` being null outside DST.
+    ///
+    ///```rust
+    /// # let tzfile = if cfg!(windows) { "c:\\Users\\nbauw\\Dev\\zoneinfo\\Europe\\Paris" } else { "/usr/share/zoneinfo/Europe/Paris" };
+    /// use chrono::{TimeZone, Utc};
+    /// use libtzfile::{Tz, TzError};
+    /// let now = Utc.with_ymd_and_hms(2020, 9, 5, 16, 41, 44).unwrap();
+    /// let tzinfo = Tz::new(tzfile)?.zoneinfo_with(&now)?;
+    /// println!("{}", tzinfo.worldtimeapi("127.0.0.1").to_json()?);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    ///```
+    ///
+    ///```text
+    /// {"abbreviation":"CEST","client_ip":"127.0.0.1","datetime":"2020-09-05T18:41:44.000000+02:00","day_of_week":6,"day_of_year":249,"dst":true,"dst_from":"2020-03-29T01:00:00+00:00","dst_offset":3600,"dst_until":"2020-10-25T01:00:00+00:00","raw_offset":3600,"timezone":"Europe/Paris","unixtime":1599324104,"utc_datetime":"2020-09-05T16:41:44.000000+00:00","utc_offset":"+02:00","week_number":36}
+    ///```
+    pub fn worldtimeapi(&self, client_ip: &str) -> WorldTimeApi {
+        worldtimeapi::worldtimeapi(self, client_ip)
+    }
 }
 
 impl Tz {
@@ -358,23 +599,40 @@ impl Tz {
         // Parses TZfile header
         let header = Tz::parse_header(&buf)?;
         // Parses data
-        Tz::parse_data(&buf, header, tz)
+        Tz::parse_data(&buf, header, &Tz::zone_name(tz)?)
+    }
+
+    #[cfg(feature = "std")]
+    /// Creates a Tz struct from TZfile data, ```name``` being the zone name (ie. Europe/Paris).
+    ///
+    ///```rust
+    /// use libtzfile::Tz;
+    /// let buf = std::fs::read("/usr/share/zoneinfo/America/Phoenix").unwrap();
+    /// let tz = Tz::from_bytes(&buf, "America/Phoenix").unwrap();
+    ///```
+    pub fn from_bytes(buf: &[u8], name: &str) -> Result<Tz, TzError> {
+        let header = Tz::parse_header(buf)?;
+        Tz::parse_data(buf, header, name)
     }
 
     fn parse_header(buffer: &[u8]) -> Result<Header, TzError> {
-        let magic = BE::read_u32(&buffer[0x00..=0x03]);
-        if magic != MAGIC {
+        if buffer.len() < 4 || BE::read_u32(&buffer[0x00..=0x03]) != MAGIC {
             return Err(TzError::InvalidMagic);
         }
-        if buffer[4] != 50 {
+        // Truncated header
+        if buffer.len() < HEADER_LEN {
+            return Err(TzError::ParseError);
+        }
+        // Versions 3 and 4 only extend the footer and leap second semantics, the layout is unchanged
+        if buffer[4] < 50 {
             return Err(TzError::UnsupportedFormat);
         }
-        let tzh_ttisutcnt = BE::read_i32(&buffer[0x14..=0x17]) as usize;
-        let tzh_ttisstdcnt = BE::read_i32(&buffer[0x18..=0x1B]) as usize;
-        let tzh_leapcnt = BE::read_i32(&buffer[0x1C..=0x1F]) as usize;
-        let tzh_timecnt = BE::read_i32(&buffer[0x20..=0x23]) as usize;
-        let tzh_typecnt = BE::read_i32(&buffer[0x24..=0x27]) as usize;
-        let tzh_charcnt = BE::read_i32(&buffer[0x28..=0x2b]) as usize;
+        let tzh_ttisutcnt = BE::read_u32(&buffer[0x14..=0x17]) as usize;
+        let tzh_ttisstdcnt = BE::read_u32(&buffer[0x18..=0x1B]) as usize;
+        let tzh_leapcnt = BE::read_u32(&buffer[0x1C..=0x1F]) as usize;
+        let tzh_timecnt = BE::read_u32(&buffer[0x20..=0x23]) as usize;
+        let tzh_typecnt = BE::read_u32(&buffer[0x24..=0x27]) as usize;
+        let tzh_charcnt = BE::read_u32(&buffer[0x28..=0x2b]) as usize;
         // V2 format data start
         let s: usize = tzh_timecnt * 5
             + tzh_typecnt * 6
@@ -383,13 +641,17 @@ impl Tz {
             + tzh_ttisstdcnt
             + tzh_ttisutcnt
             + 44;
+        // Truncated file
+        if buffer.len() < s + HEADER_LEN {
+            return Err(TzError::ParseError);
+        }
         Ok(Header {
-            tzh_ttisutcnt: BE::read_i32(&buffer[s + 0x14..=s + 0x17]) as usize,
-            tzh_ttisstdcnt: BE::read_i32(&buffer[s + 0x18..=s + 0x1B]) as usize,
-            tzh_leapcnt: BE::read_i32(&buffer[s + 0x1C..=s + 0x1F]) as usize,
-            tzh_timecnt: BE::read_i32(&buffer[s + 0x20..=s + 0x23]) as usize,
-            tzh_typecnt: BE::read_i32(&buffer[s + 0x24..=s + 0x27]) as usize,
-            tzh_charcnt: BE::read_i32(&buffer[s + 0x28..=s + 0x2b]) as usize,
+            tzh_ttisutcnt: BE::read_u32(&buffer[s + 0x14..=s + 0x17]) as usize,
+            tzh_ttisstdcnt: BE::read_u32(&buffer[s + 0x18..=s + 0x1B]) as usize,
+            tzh_leapcnt: BE::read_u32(&buffer[s + 0x1C..=s + 0x1F]) as usize,
+            tzh_timecnt: BE::read_u32(&buffer[s + 0x20..=s + 0x23]) as usize,
+            tzh_typecnt: BE::read_u32(&buffer[s + 0x24..=s + 0x27]) as usize,
+            tzh_charcnt: BE::read_u32(&buffer[s + 0x28..=s + 0x2b]) as usize,
             v2_header_start: s,
         })
     }
@@ -403,8 +665,12 @@ impl Tz {
         let tzh_charcnt_len: usize = header.tzh_charcnt;
         let tzh_timecnt_end: usize = HEADER_LEN + header.v2_header_start + tzh_timecnt_len;
         let tzh_typecnt_end: usize = tzh_timecnt_end + tzh_typecnt_len;
-        let tzh_leapcnt_end: usize = tzh_typecnt_end + tzh_leapcnt_len;
-        let tzh_charcnt_end: usize = tzh_leapcnt_end + tzh_charcnt_len;
+        let tzh_charcnt_end: usize = tzh_typecnt_end + tzh_charcnt_len;
+        let tzh_leapcnt_end: usize = tzh_charcnt_end + tzh_leapcnt_len;
+        // Truncated file
+        if buffer.len() < tzh_leapcnt_end {
+            return Err(TzError::ParseError);
+        }
 
         // Extracting data fields
         let tzh_timecnt_data: Vec<i64> = buffer[HEADER_LEN + header.v2_header_start
@@ -416,24 +682,8 @@ impl Tz {
         let tzh_timecnt_indices: &[u8] =
             &buffer[HEADER_LEN + header.v2_header_start + header.tzh_timecnt * 8..tzh_timecnt_end];
 
-        let abbrs = from_utf8(&buffer[tzh_leapcnt_end..tzh_charcnt_end]).unwrap();
-
-        let tzh_typecnt: Vec<Ttinfo> = buffer[tzh_timecnt_end..tzh_typecnt_end]
-            .chunks_exact(6)
-            .map(|tti| {
-                let offset = tti[5];
-                let index = abbrs
-                    .chars()
-                    .take(offset as usize)
-                    .filter(|x| *x == '\0')
-                    .count();
-                Ttinfo {
-                    tt_utoff: BE::read_i32(&tti[0..4]) as isize,
-                    tt_isdst: tti[4],
-                    tt_abbrind: index as u8,
-                }
-            })
-            .collect();
+        let abbrs = from_utf8(&buffer[tzh_typecnt_end..tzh_charcnt_end])
+            .map_err(|_| TzError::BadUtf8String)?;
 
         let mut tz_abbr: Vec<String> = abbrs.split("\u{0}").map(|st| st.to_string()).collect();
         // Removes last empty char
@@ -441,16 +691,59 @@ impl Tz {
             return Err(TzError::EmptyString);
         };
 
-        Ok(Tz {
+        let mut tzh_typecnt: Vec<Ttinfo> = Vec::with_capacity(header.tzh_typecnt);
+        for tti in buffer[tzh_timecnt_end..tzh_typecnt_end].chunks_exact(6) {
+            // Abbreviations may share a suffix (ie. HST in AHST): the abbreviation is read at its offset
+            let abbr = match abbrs
+                .get(tti[5] as usize..)
+                .and_then(|a| a.split_once('\0'))
+            {
+                Some((abbr, _)) => abbr,
+                None => return Err(TzError::ParseError),
+            };
+            let index = match tz_abbr.iter().position(|a| a == abbr) {
+                Some(i) => i,
+                None => {
+                    tz_abbr.push(abbr.to_string());
+                    tz_abbr.len() - 1
+                }
+            };
+            tzh_typecnt.push(Ttinfo {
+                tt_utoff: BE::read_i32(&tti[0..4]) as isize,
+                tt_isdst: tti[4],
+                tt_abbrind: u8::try_from(index).map_err(|_| TzError::ParseError)?,
+            });
+        }
+
+        let tzh_leapcnt_data: Vec<LeapSecond> = buffer[tzh_charcnt_end..tzh_leapcnt_end]
+            .chunks_exact(12)
+            .map(|ls| LeapSecond {
+                ls_trans: BE::read_i64(&ls[0..8]),
+                ls_corr: BE::read_i32(&ls[8..12]),
+            })
+            .collect();
+
+        // TZ string footer, after the standard/wall and UT/local indicators
+        let tz_footer = PosixTz::from_footer(
+            buffer
+                .get(tzh_leapcnt_end + header.tzh_ttisstdcnt + header.tzh_ttisutcnt..)
+                .unwrap_or_default(),
+        )?;
+
+        Tz {
+            tzh_version: buffer[4] - b'0',
             tzh_timecnt_data,
             tzh_timecnt_indices: tzh_timecnt_indices.to_vec(),
             tzh_typecnt,
             tz_abbr,
-        })
+            tzh_leapcnt_data,
+            tz_footer,
+        }
+        .checked()
     }
 
     #[cfg(feature = "std")]
-    fn parse_data(buffer: &[u8], header: Header, filename: &str) -> Result<Tz, TzError> {
+    fn parse_data(buffer: &[u8], header: Header, name: &str) -> Result<Tz, TzError> {
         // Calculates fields lengths and indexes (Version 2 format)
         let tzh_timecnt_len: usize = header.tzh_timecnt * 9;
         let tzh_typecnt_len: usize = header.tzh_typecnt * 6;
@@ -458,8 +751,12 @@ impl Tz {
         let tzh_charcnt_len: usize = header.tzh_charcnt;
         let tzh_timecnt_end: usize = HEADER_LEN + header.v2_header_start + tzh_timecnt_len;
         let tzh_typecnt_end: usize = tzh_timecnt_end + tzh_typecnt_len;
-        let tzh_leapcnt_end: usize = tzh_typecnt_end + tzh_leapcnt_len;
-        let tzh_charcnt_end: usize = tzh_leapcnt_end + tzh_charcnt_len;
+        let tzh_charcnt_end: usize = tzh_typecnt_end + tzh_charcnt_len;
+        let tzh_leapcnt_end: usize = tzh_charcnt_end + tzh_leapcnt_len;
+        // Truncated file
+        if buffer.len() < tzh_leapcnt_end {
+            return Err(TzError::ParseError);
+        }
 
         // Extracting data fields
         let tzh_timecnt_data: Vec<i64> = buffer[HEADER_LEN + header.v2_header_start
@@ -471,24 +768,7 @@ impl Tz {
         let tzh_timecnt_indices: &[u8] =
             &buffer[HEADER_LEN + header.v2_header_start + header.tzh_timecnt * 8..tzh_timecnt_end];
 
-        let abbrs = from_utf8(&buffer[tzh_leapcnt_end..tzh_charcnt_end])?;
-
-        let tzh_typecnt: Vec<Ttinfo> = buffer[tzh_timecnt_end..tzh_typecnt_end]
-            .chunks_exact(6)
-            .map(|tti| {
-                let offset = tti[5];
-                let index = abbrs
-                    .chars()
-                    .take(offset as usize)
-                    .filter(|x| *x == '\0')
-                    .count();
-                Ttinfo {
-                    tt_utoff: BE::read_i32(&tti[0..4]) as isize,
-                    tt_isdst: tti[4],
-                    tt_abbrind: index as u8,
-                }
-            })
-            .collect();
+        let abbrs = from_utf8(&buffer[tzh_typecnt_end..tzh_charcnt_end])?;
 
         let mut tz_abbr: Vec<String> = abbrs.split('\u{0}').map(|st| st.to_string()).collect();
         // Removes last empty char
@@ -496,7 +776,100 @@ impl Tz {
             return Err(TzError::EmptyString);
         };
 
-        // Generating zone name (ie. Europe/Paris) from requested file name
+        let mut tzh_typecnt: Vec<Ttinfo> = Vec::with_capacity(header.tzh_typecnt);
+        for tti in buffer[tzh_timecnt_end..tzh_typecnt_end].chunks_exact(6) {
+            // Abbreviations may share a suffix (ie. HST in AHST): the abbreviation is read at its offset
+            let abbr = match abbrs
+                .get(tti[5] as usize..)
+                .and_then(|a| a.split_once('\0'))
+            {
+                Some((abbr, _)) => abbr,
+                None => return Err(TzError::ParseError),
+            };
+            let index = match tz_abbr.iter().position(|a| a == abbr) {
+                Some(i) => i,
+                None => {
+                    tz_abbr.push(abbr.to_string());
+                    tz_abbr.len() - 1
+                }
+            };
+            tzh_typecnt.push(Ttinfo {
+                tt_utoff: BE::read_i32(&tti[0..4]) as isize,
+                tt_isdst: tti[4],
+                tt_abbrind: u8::try_from(index).map_err(|_| TzError::ParseError)?,
+            });
+        }
+
+        let tzh_leapcnt_data: Vec<LeapSecond> = buffer[tzh_charcnt_end..tzh_leapcnt_end]
+            .chunks_exact(12)
+            .map(|ls| LeapSecond {
+                ls_trans: BE::read_i64(&ls[0..8]),
+                ls_corr: BE::read_i32(&ls[8..12]),
+            })
+            .collect();
+
+        // TZ string footer, after the standard/wall and UT/local indicators
+        let tz_footer = PosixTz::from_footer(
+            buffer
+                .get(tzh_leapcnt_end + header.tzh_ttisstdcnt + header.tzh_ttisutcnt..)
+                .unwrap_or_default(),
+        )?;
+
+        #[cfg(any(feature = "parse", feature = "json"))]
+        {
+            return Tz {
+                tzh_version: buffer[4] - b'0',
+                tzh_timecnt_data,
+                tzh_timecnt_indices: tzh_timecnt_indices.to_vec(),
+                tzh_typecnt,
+                tz_abbr,
+                tzh_leapcnt_data,
+                tz_footer,
+                name: name.to_string(),
+            }
+            .checked();
+        }
+
+        #[cfg(not(any(feature = "parse", feature = "json")))]
+        {
+            let _ = name;
+            Tz {
+                tzh_version: buffer[4] - b'0',
+                tzh_timecnt_data,
+                tzh_timecnt_indices: tzh_timecnt_indices.to_vec(),
+                tzh_typecnt,
+                tz_abbr,
+                tzh_leapcnt_data,
+                tz_footer,
+            }
+            .checked()
+        }
+    }
+
+    // Checks the tables the lookups rely on: at least one local time type, local time type and abbreviation indices in
+    // range, transition times in ascending order, and a footer matching its TZ string
+    fn checked(self) -> Result<Tz, TzError> {
+        if self.tzh_typecnt.is_empty()
+            || self.tzh_timecnt_indices.len() != self.tzh_timecnt_data.len()
+            || self
+                .tzh_timecnt_indices
+                .iter()
+                .any(|&i| i as usize >= self.tzh_typecnt.len())
+            || self
+                .tzh_typecnt
+                .iter()
+                .any(|tti| tti.tt_abbrind as usize >= self.tz_abbr.len())
+            || self.tzh_timecnt_data.windows(2).any(|t| t[0] > t[1])
+            || self.tz_footer.as_ref().is_some_and(|f| !f.is_consistent())
+        {
+            return Err(TzError::ParseError);
+        }
+        Ok(self)
+    }
+
+    #[cfg(any(feature = "std", feature = "parse", feature = "json"))]
+    // Generating zone name (ie. Europe/Paris) from requested file name
+    fn zone_name(filename: &str) -> Result<String, TzError> {
         let mut timezone = String::new();
         #[cfg(not(windows))]
         let mut tz: Vec<&str> = filename.split('/').collect();
@@ -514,25 +887,7 @@ impl Tz {
             timezone.push('/');
         }
         timezone.push_str(tz[1]);
-
-        #[cfg(any(feature = "parse", feature = "json"))]
-        {
-            return Ok(Tz {
-                tzh_timecnt_data,
-                tzh_timecnt_indices: tzh_timecnt_indices.to_vec(),
-                tzh_typecnt,
-                tz_abbr,
-                name: timezone,
-            });
-        }
-
-        #[cfg(not(any(feature = "parse", feature = "json")))]
-        Ok(Tz {
-            tzh_timecnt_data,
-            tzh_timecnt_indices: tzh_timecnt_indices.to_vec(),
-            tzh_typecnt,
-            tz_abbr,
-        })
+        Ok(timezone)
     }
 
     #[cfg(any(feature = "std", feature = "parse", feature = "json"))]
@@ -545,9 +900,9 @@ impl Tz {
 
     #[cfg(any(feature = "parse", feature = "json"))]
     /// Returns year's transition times for a timezone.
-    /// If year is Some(0), returns current year's transition times.
+    /// If year is YearSelection::Current (or Some(0)), returns current year's transition times.
     /// If there's no transition time for selected year, returns the last occured transition time (zone's current parameters).
-    /// If no year (None) is specified, returns all transition times recorded in the TZfile .
+    /// If no year (YearSelection::All or None) is specified, returns all transition times recorded in the TZfile .
     ///
     /// ```rust
     /// # let tzfile = if cfg!(windows) { "c:\\Users\\nbauw\\Dev\\zoneinfo\\Europe\\Paris" } else { "/usr/share/zoneinfo/Europe/Paris" };
@@ -558,7 +913,32 @@ impl Tz {
     /// ```text
     /// [TransitionTime { time: 2020-03-29T01:00:00Z, utc_offset: 7200, isdst: true, abbreviation: "CEST" }, TransitionTime { time: 2020-10-25T01:00:00Z, utc_offset: 3600, isdst: false, abbreviation: "CET" }]
     /// ```
-    pub fn transition_times(&self, y: Option<i32>) -> Result<Vec<TransitionTime>, TzError> {
+    pub fn transition_times(
+        &self,
+        y: impl Into<YearSelection>,
+    ) -> Result<Vec<TransitionTime>, TzError> {
+        self.transition_times_with(y, &SystemClock)
+    }
+
+    #[cfg(any(feature = "parse", feature = "json"))]
+    /// Same as transition_times, the current year being given by a clock.
+    ///
+    /// ```rust
+    /// # let tzfile = if cfg!(windows) { "c:\\Users\\nbauw\\Dev\\zoneinfo\\Europe\\Paris" } else { "/usr/share/zoneinfo/Europe/Paris" };
+    /// use chrono::{TimeZone, Utc};
+    /// use libtzfile::{Tz, YearSelection};
+    /// let now = Utc.with_ymd_and_hms(2020, 6, 1, 0, 0, 0).unwrap();
+    /// println!("{:?}", Tz::new(tzfile).unwrap().transition_times_with(YearSelection::Current, &now).unwrap());
+    /// ```
+    ///
+    /// ```text
+    /// [TransitionTime { time: 2020-03-29T01:00:00Z, utc_offset: 7200, isdst: true, abbreviation: "CEST" }, TransitionTime { time: 2020-10-25T01:00:00Z, utc_offset: 3600, isdst: false, abbreviation: "CET" }]
+    /// ```
+    pub fn transition_times_with(
+        &self,
+        y: impl Into<YearSelection>,
+        clock: &impl Clock,
+    ) -> Result<Vec<TransitionTime>, TzError> {
         let timezone = self;
 
         // Fix for issue #3 "Calling zoneinfo on a file without transition times panics"
@@ -575,15 +955,12 @@ impl Tz {
         let mut parsedtimechanges = Vec::new();
 
         // Get and store the transition time indices for requested
-        if y.is_some() {
-            let d = Utc::now();
-            let y = y.unwrap();
-            // year = 0 ? current year is requested
-            let y = if y == 0 {
-                d.format("%Y").to_string().parse()?
-            } else {
-                y
-            };
+        let y = match y.into() {
+            YearSelection::All => None,
+            YearSelection::Current => Some(clock.now().year()),
+            YearSelection::Year(y) => Some(y),
+        };
+        if let Some(y) = y {
             // for year comparison
             // We can use unwrap safely with Utc:
             // (from Chrono doc) unwrap() is best combined with time zone types where the mapping can never fail like Utc and FixedOffset.
@@ -605,7 +982,7 @@ impl Tz {
             // No year requested ? stores all transition times
             for t in 0..timezone.tzh_timecnt_data.len() {
                 /* patch : chrono panics on an overflowing timestamp, and a 0xF800000000000000 timestamp is present in some Debian 10 TZfiles.*/
-                if timezone.tzh_timecnt_data[t] != -576460752303423488 {
+                if timezone.tzh_timecnt_data[t] != BIG_BANG {
                     timechanges.push(t)
                 };
             }
@@ -654,6 +1031,468 @@ impl Tz {
         Ok(parsedtimechanges)
     }
 
+    #[cfg(any(feature = "parse", feature = "json"))]
+    /// Returns the transition times occurring from ```start``` (included) to ```end``` (excluded).
+    /// After the last transition time recorded in the TZfile, transition times are generated from the TZ string footer.
+    ///
+    /// ```rust
+    /// # let tzfile = if cfg!(windows) { "c:\\Users\\nbauw\\Dev\\zoneinfo\\Europe\\Paris" } else { "/usr/share/zoneinfo/Europe/Paris" };
+    /// use chrono::{TimeZone, Utc};
+    /// use libtzfile::Tz;
+    /// let tz = Tz::new(tzfile).unwrap();
+    /// let start = Utc.with_ymd_and_hms(2050, 1, 1, 0, 0, 0).unwrap();
+    /// let end = Utc.with_ymd_and_hms(2051, 1, 1, 0, 0, 0).unwrap();
+    /// println!("{:?}", tz.transitions_between(start, end));
+    /// ```
+    ///
+    /// ```text
+    /// [TransitionTime { time: 2050-03-27T01:00:00Z, utc_offset: 7200, isdst: true, abbreviation: "CEST" }, TransitionTime { time: 2050-10-30T01:00:00Z, utc_offset: 3600, isdst: false, abbreviation: "CET" }]
+    /// ```
+    pub fn transitions_between(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Vec<TransitionTime> {
+        let (start, end) = (start.timestamp(), end.timestamp());
+        let mut transitions = Vec::new();
+        if start >= end {
+            return transitions;
+        }
+
+        // Recorded transition times
+        let first = self.tzh_timecnt_data.partition_point(|&t| t < start);
+        let last = self.tzh_timecnt_data.partition_point(|&t| t < end);
+        for i in first..last {
+            let ttinfo = self.ttinfo_at(Some(i));
+            transitions.push(TransitionTime {
+                time: Utc.timestamp_opt(self.tzh_timecnt_data[i], 0).unwrap(),
+                utc_offset: ttinfo.tt_utoff,
+                isdst: ttinfo.tt_isdst == 1,
+                abbreviation: self.tz_abbr[ttinfo.tt_abbrind as usize].clone(),
+            });
+        }
+
+        // Transition times generated from the footer
+        if let Some(footer) = &self.tz_footer {
+            let recorded_end = self.tzh_timecnt_data.last().copied();
+            let from = recorded_end.map_or(start, |t| t.max(start));
+            for year in posix::year_of(from)..=posix::year_of(end) {
+                for (t, isdst) in footer.transitions(year).into_iter().flatten() {
+                    if t >= start && t < end && recorded_end.is_none_or(|r| t > r) {
+                        let (utc_offset, isdst, abbreviation) = footer.ttinfo(isdst);
+                        transitions.push(TransitionTime {
+                            time: Utc.timestamp_opt(t, 0).unwrap(),
+                            utc_offset,
+                            isdst,
+                            abbreviation: abbreviation.to_string(),
+                        });
+                    }
+                }
+            }
+        }
+        transitions
+    }
+
+    #[cfg(any(feature = "parse", feature = "json"))]
+    /// Returns a lazy iterator over the zone's transitions occurring at or after an instant, first from the TZfile
+    /// and then indefinitely from the TZ string footer. It is not double-ended: an iterator without an end cannot have
+    /// ```next_back``` meet ```next```, as ```DoubleEndedIterator``` requires, so ```transitions_before``` walks backward.
+    ///
+    /// ```rust
+    /// # let tzfile = if cfg!(windows) { "c:\\Users\\nbauw\\Dev\\zoneinfo\\Europe\\Paris" } else { "/usr/share/zoneinfo/Europe/Paris" };
+    /// use chrono::{TimeZone, Utc};
+    /// use libtzfile::Tz;
+    /// let tz = Tz::new(tzfile).unwrap();
+    /// let mut transitions = tz.transitions_from(Utc.with_ymd_and_hms(2020, 6, 1, 0, 0, 0).unwrap());
+    /// println!("{:?}", transitions.next());
+    /// ```
+    ///
+    /// ```text
+    /// Some(Transition { time: 2020-10-25T01:00:00Z, old_utc_offset: 7200, new_utc_offset: 3600, isdst: false, abbreviation: "CET" })
+    /// ```
+    pub fn transitions_from(&self, instant: DateTime<Utc>) -> Transitions<'_> {
+        Transitions::new(self, instant)
+    }
+
+    #[cfg(any(feature = "parse", feature = "json"))]
+    /// Returns a lazy iterator over the zone's transitions occurring before an instant, most recent first, down to the
+    /// first transition recorded in the TZfile.
+    ///
+    /// ```rust
+    /// # let tzfile = if cfg!(windows) { "c:\\Users\\nbauw\\Dev\\zoneinfo\\Europe\\Paris" } else { "/usr/share/zoneinfo/Europe/Paris" };
+    /// use chrono::{TimeZone, Utc};
+    /// use libtzfile::Tz;
+    /// let tz = Tz::new(tzfile).unwrap();
+    /// let mut transitions = tz.transitions_before(Utc.with_ymd_and_hms(2020, 6, 1, 0, 0, 0).unwrap());
+    /// println!("{:?}", transitions.next());
+    /// ```
+    ///
+    /// ```text
+    /// Some(Transition { time: 2020-03-29T01:00:00Z, old_utc_offset: 3600, new_utc_offset: 7200, isdst: true, abbreviation: "CEST" })
+    /// ```
+    pub fn transitions_before(&self, instant: DateTime<Utc>) -> TransitionsBefore<'_> {
+        TransitionsBefore::new(self, instant)
+    }
+
+    #[cfg(any(feature = "parse", feature = "json"))]
+    /// Returns the first transition changing the local time type (offset, DST or abbreviation) strictly after an instant.
+    /// Returns None if no transition is recorded or generated from the footer after that instant.
+    ///
+    /// ```rust
+    /// # let tzfile = if cfg!(windows) { "c:\\Users\\nbauw\\Dev\\zoneinfo\\Europe\\Paris" } else { "/usr/share/zoneinfo/Europe/Paris" };
+    /// use chrono::{TimeZone, Utc};
+    /// use libtzfile::Tz;
+    /// let tz = Tz::new(tzfile).unwrap();
+    /// println!("{:?}", tz.next_transition(Utc.with_ymd_and_hms(2020, 6, 1, 0, 0, 0).unwrap()));
+    /// ```
+    ///
+    /// ```text
+    /// Some(Transition { time: 2020-10-25T01:00:00Z, old_utc_offset: 7200, new_utc_offset: 3600, isdst: false, abbreviation: "CET" })
+    /// ```
+    pub fn next_transition(&self, after: DateTime<Utc>) -> Option<Transition> {
+        let from = Utc.timestamp_opt(after.timestamp() + 1, 0).single()?;
+        self.transitions_from(from)
+            .find(|tt| self.changes_local_type(tt))
+    }
+
+    #[cfg(any(feature = "parse", feature = "json"))]
+    /// Returns the last transition changing the local time type (offset, DST or abbreviation) strictly before an instant.
+    /// Returns None if the instant is before the first recorded transition time.
+    ///
+    /// ```rust
+    /// # let tzfile = if cfg!(windows) { "c:\\Users\\nbauw\\Dev\\zoneinfo\\Europe\\Paris" } else { "/usr/share/zoneinfo/Europe/Paris" };
+    /// use chrono::{TimeZone, Utc};
+    /// use libtzfile::Tz;
+    /// let tz = Tz::new(tzfile).unwrap();
+    /// println!("{:?}", tz.previous_transition(Utc.with_ymd_and_hms(2020, 6, 1, 0, 0, 0).unwrap()));
+    /// ```
+    ///
+    /// ```text
+    /// Some(Transition { time: 2020-03-29T01:00:00Z, old_utc_offset: 3600, new_utc_offset: 7200, isdst: true, abbreviation: "CEST" })
+    /// ```
+    pub fn previous_transition(&self, before: DateTime<Utc>) -> Option<Transition> {
+        self.transitions_before(before)
+            .find(|tt| self.changes_local_type(tt))
+    }
+
+    #[cfg(any(feature = "parse", feature = "json"))]
+    // Some TZfiles record transitions to an identical local time type (ie. at 2^31 - 1 in "fat" files)
+    fn changes_local_type(&self, tt: &Transition) -> bool {
+        let (utc_offset, isdst, abbreviation) = self.local_type_at(tt.time.timestamp() - 1);
+        utc_offset != tt.new_utc_offset || isdst != tt.isdst || abbreviation != tt.abbreviation
+    }
+
+    #[cfg(any(feature = "parse", feature = "json"))]
+    /// Returns the local time type (offset to UTC, DST, abbreviation) in effect at an instant.
+    /// After the last transition time recorded in the TZfile, it is computed from the TZ string footer.
+    ///
+    /// ```rust
+    /// # let tzfile = if cfg!(windows) { "c:\\Users\\nbauw\\Dev\\zoneinfo\\Europe\\Paris" } else { "/usr/share/zoneinfo/Europe/Paris" };
+    /// use chrono::{TimeZone, Utc};
+    /// use libtzfile::Tz;
+    /// let tz = Tz::new(tzfile).unwrap();
+    /// println!("{:?}", tz.lookup(Utc.with_ymd_and_hms(2050, 7, 1, 0, 0, 0).unwrap()));
+    /// ```
+    ///
+    /// ```text
+    /// LocalTimeType { utc_offset: 7200, isdst: true, abbreviation: "CEST" }
+    /// ```
+    pub fn lookup(&self, instant: DateTime<Utc>) -> LocalTimeType {
+        let (utc_offset, isdst, abbreviation) = self.local_type_at(instant.timestamp());
+        LocalTimeType {
+            utc_offset,
+            isdst,
+            abbreviation: abbreviation.to_string(),
+        }
+    }
+
+    #[cfg(any(feature = "parse", feature = "json"))]
+    /// Returns the instant(s) a local time maps to: a single one, two for an ambiguous local time (ie. when DST ends),
+    /// or the instants around the transition for a skipped local time (ie. when DST starts).
+    ///
+    /// ```rust
+    /// # let tzfile = if cfg!(windows) { "c:\\Users\\nbauw\\Dev\\zoneinfo\\Europe\\Paris" } else { "/usr/share/zoneinfo/Europe/Paris" };
+    /// use chrono::NaiveDate;
+    /// use libtzfile::Tz;
+    /// let tz = Tz::new(tzfile).unwrap();
+    /// let local = NaiveDate::from_ymd_opt(2025, 10, 26).unwrap().and_hms_opt(2, 30, 0).unwrap();
+    /// println!("{:?}", tz.local_mapping(local).unwrap());
+    /// ```
+    ///
+    /// ```text
+    /// Ambiguous(2025-10-26T00:30:00Z, 2025-10-26T01:30:00Z)
+    /// ```
+    pub fn local_mapping(&self, local: NaiveDateTime) -> Result<LocalMapping, TzError> {
+        local::local_mapping(self, local)
+    }
+
+    #[cfg(any(feature = "parse", feature = "json"))]
+    /// Converts a local time to UTC, ambiguous and skipped local times being handled according to a disambiguation policy.
+    ///
+    /// ```rust
+    /// # let tzfile = if cfg!(windows) { "c:\\Users\\nbauw\\Dev\\zoneinfo\\Europe\\Paris" } else { "/usr/share/zoneinfo/Europe/Paris" };
+    /// use chrono::NaiveDate;
+    /// use libtzfile::{Disambiguation, Tz};
+    /// let tz = Tz::new(tzfile).unwrap();
+    /// let local = NaiveDate::from_ymd_opt(2025, 3, 30).unwrap().and_hms_opt(2, 30, 0).unwrap();
+    /// println!("{}", tz.local_to_utc(local, Disambiguation::Compatible).unwrap());
+    /// ```
+    ///
+    /// ```text
+    /// 2025-03-30 01:30:00 UTC
+    /// ```
+    pub fn local_to_utc(
+        &self,
+        local: NaiveDateTime,
+        disambiguation: Disambiguation,
+    ) -> Result<DateTime<Utc>, TzError> {
+        self.local_mapping(local)?.resolve(disambiguation)
+    }
+
+    #[cfg(any(feature = "parse", feature = "json"))]
+    /// Formats an instant in the zone's local time, with strftime-like directives (see chrono::format::strftime).
+    /// The zone-dependent directives are computed from the TZfile data:
+    /// ```%Z``` is the TZ abbreviation, ```%z```, ```%:z```, ```%::z``` and ```%:::z``` are the offset to UTC
+    /// (including the seconds of LMT offsets), and ```%s``` is the number of seconds since the Unix epoch.
+    ///
+    /// ```rust
+    /// # let tzfile = if cfg!(windows) { "c:\\Users\\nbauw\\Dev\\zoneinfo\\Europe\\Paris" } else { "/usr/share/zoneinfo/Europe/Paris" };
+    /// use chrono::{TimeZone, Utc};
+    /// use libtzfile::Tz;
+    /// let tz = Tz::new(tzfile).unwrap();
+    /// let instant = Utc.with_ymd_and_hms(1890, 6, 1, 12, 0, 0).unwrap();
+    /// println!("{}", tz.format(instant, "%a %d %b %Y %H:%M:%S %Z %::z").unwrap());
+    /// ```
+    ///
+    /// ```text
+    /// Sun 01 Jun 1890 12:09:21 LMT +00:09:21
+    /// ```
+    pub fn format(&self, instant: DateTime<Utc>, fmt: &str) -> Result<String, TzError> {
+        let (utc_offset, _, abbreviation) = self.local_type_at(instant.timestamp());
+        let offset = FixedOffset::east_opt(utc_offset as i32).ok_or(TzError::InvalidTimezone)?;
+        format::strftime(&instant.with_timezone(&offset), abbreviation, fmt)
+    }
+
+    #[cfg(any(feature = "parse", feature = "json"))]
+    /// Formats an instant as an RFC 9557 date and time, ie. ```2025-06-01T12:00:00+02:00[Europe/Paris]```.
+    /// The zone annotation is marked critical (```[!Europe/Paris]```) if requested. As RFC 3339 offsets have no seconds,
+    /// LMT offsets are rounded to the minute.
+    ///
+    /// ```rust
+    /// # let tzfile = if cfg!(windows) { "c:\\Users\\nbauw\\Dev\\zoneinfo\\Europe\\Paris" } else { "/usr/share/zoneinfo/Europe/Paris" };
+    /// use chrono::{TimeZone, Utc};
+    /// use libtzfile::Tz;
+    /// let tz = Tz::new(tzfile).unwrap();
+    /// let instant = Utc.with_ymd_and_hms(2025, 6, 1, 10, 0, 0).unwrap();
+    /// println!("{}", tz.format_ixdtf(instant, true).unwrap());
+    /// ```
+    ///
+    /// ```text
+    /// 2025-06-01T12:00:00+02:00[!Europe/Paris]
+    /// ```
+    pub fn format_ixdtf(&self, instant: DateTime<Utc>, critical: bool) -> Result<String, TzError> {
+        ixdtf::format_ixdtf(self, instant, critical)
+    }
+
+    #[cfg(any(feature = "parse", feature = "json"))]
+    /// Exports the zone as an iCalendar (RFC 5545) VTIMEZONE component, starting at a given year to keep it compact.
+    /// The local time type in effect at the start year is the first observance. The recorded transition times that follow are
+    /// listed as RDATEs in STANDARD and DAYLIGHT observances, and the TZ string footer rule gives the RRULEs.
+    /// A footer rule which cannot be written as an RRULE is listed as RDATEs, over the 50 years following its first transition.
+    ///
+    /// ```rust
+    /// # let tzfile = if cfg!(windows) { "c:\\Users\\nbauw\\Dev\\zoneinfo\\Europe\\Paris" } else { "/usr/share/zoneinfo/Europe/Paris" };
+    /// use libtzfile::Tz;
+    /// let tz = Tz::new(tzfile).unwrap();
+    /// print!("{}", tz.vtimezone(2020).unwrap());
+    /// ```
+    ///
+    /// ```text
+    /// BEGIN:VTIMEZONE
+    /// TZID:Europe/Paris
+    /// BEGIN:STANDARD
+    /// TZOFFSETFROM:+0100
+    /// TZOFFSETTO:+0100
+    /// TZNAME:CET
+    /// DTSTART:20200101T000000
+    /// END:STANDARD
+    /// BEGIN:DAYLIGHT
+    /// TZOFFSETFROM:+0100
+    /// TZOFFSETTO:+0200
+    /// TZNAME:CEST
+    /// DTSTART:20200329T020000
+    /// RRULE:FREQ=YEARLY;BYMONTH=3;BYDAY=-1SU
+    /// END:DAYLIGHT
+    /// BEGIN:STANDARD
+    /// TZOFFSETFROM:+0200
+    /// TZOFFSETTO:+0100
+    /// TZNAME:CET
+    /// DTSTART:20201025T030000
+    /// RRULE:FREQ=YEARLY;BYMONTH=10;BYDAY=-1SU
+    /// END:STANDARD
+    /// END:VTIMEZONE
+    /// ```
+    pub fn vtimezone(&self, start_year: i32) -> Result<String, TzError> {
+        ical::vtimezone(self, start_year)
+    }
+
+    #[cfg(any(feature = "parse", feature = "json"))]
+    /// Creates a Tz struct from an iCalendar (RFC 5545) VTIMEZONE component, ie. one embedded in an invitation.
+    /// The DTSTART, RDATEs and RRULE occurrences of its observances become the transition times, and the rules still
+    /// in force become the TZ string footer when they can be written as one (otherwise they are expanded up to 2037, or up
+    /// to the year following the last irregular transition if later).
+    /// The zone name is the TZID.
+    ///
+    /// ```rust
+    /// use chrono::{TimeZone, Utc};
+    /// use libtzfile::Tz;
+    /// let ics = "BEGIN:VTIMEZONE\r\n\
+    ///     TZID:W. Europe Standard Time\r\n\
+    ///     BEGIN:STANDARD\r\n\
+    ///     DTSTART:16010101T030000\r\n\
+    ///     TZOFFSETFROM:+0200\r\n\
+    ///     TZOFFSETTO:+0100\r\n\
+    ///     RRULE:FREQ=YEARLY;BYDAY=-1SU;BYMONTH=10\r\n\
+    ///     END:STANDARD\r\n\
+    ///     BEGIN:DAYLIGHT\r\n\
+    ///     DTSTART:16010101T020000\r\n\
+    ///     TZOFFSETFROM:+0100\r\n\
+    ///     TZOFFSETTO:+0200\r\n\
+    ///     RRULE:FREQ=YEARLY;BYDAY=-1SU;BYMONTH=3\r\n\
+    ///     END:DAYLIGHT\r\n\
+    ///     END:VTIMEZONE\r\n";
+    /// let tz = Tz::from_vtimezone(ics).unwrap();
+    /// println!("{}", tz.tz_footer.as_ref().unwrap().raw);
+    /// println!("{:?}", tz.lookup(Utc.with_ymd_and_hms(2025, 7, 1, 0, 0, 0).unwrap()));
+    /// ```
+    ///
+    /// ```text
+    /// <+01>-1<+02>,M3.5.0,M10.5.0/3
+    /// LocalTimeType { utc_offset: 7200, isdst: true, abbreviation: "+02" }
+    /// ```
+    pub fn from_vtimezone(ics: &str) -> Result<Tz, TzError> {
+        ical::from_vtimezone(ics)
+    }
+
+    #[cfg(any(feature = "parse", feature = "json"))]
+    /// Decompiles the zone into approximate zic source lines, ie. to diff a device's zoneinfo against the tzdata source.
+    /// The periods of constant standard offset and abbreviation become the Zone lines, the transitions in and out of DST
+    /// become Rule lines, folded over consecutive years when they follow the same rule (ie. ```lastSun```), and the TZ
+    /// string footer becomes the rules without end year. The rules are named after the zone (ie. Paris), and their times
+    /// are wall clock times, or UTC times (```u``` suffix) when they start a Zone line.
+    ///
+    /// ```rust
+    /// # let tzfile = if cfg!(windows) { "c:\\Users\\nbauw\\Dev\\zoneinfo\\Europe\\Paris" } else { "/usr/share/zoneinfo/Europe/Paris" };
+    /// use libtzfile::Tz;
+    /// let tz = Tz::new(tzfile).unwrap();
+    /// print!("{}", tz.zic_source().unwrap());
+    /// ```
+    ///
+    /// ```text
+    /// # Rule  NAME    FROM    TO      -       IN      ON      AT      SAVE    LETTER/S
+    /// Rule    Paris   1916    only    -       Jun     14      23:00   1:00    S
+    /// (...)
+    /// Rule    Paris   1981    max     -       Mar     lastSun 2:00    1:00    S
+    /// Rule    Paris   1996    max     -       Oct     lastSun 3:00    0       -
+    /// # Zone  NAME    STDOFF  RULES   FORMAT  [UNTIL]
+    /// Zone    Europe/Paris    0:09:21 -       LMT     1891 Mar 16
+    ///                         0:09:21 -       PMT     1911 Mar 11
+    ///                         0:00    Paris   WE%sT   1940 Jun 14 23:00
+    ///                         1:00    Paris   CE%sT   1944 Aug 25
+    ///                         1:00    Paris   WE%sT   1945 Sep 16 3:00
+    ///                         1:00    Paris   CE%sT
+    /// ```
+    pub fn zic_source(&self) -> Result<String, TzError> {
+        zic::zic_source(self)
+    }
+
+    #[cfg(any(feature = "parse", feature = "json"))]
+    /// Returns the history of the zone as contiguous eras of constant standard offset and abbreviation pattern, as the Zone
+    /// lines of the tzdata source: start and end, standard offset, DST rules in force, DST save amount and abbreviation
+    /// pattern. They are computed from the recorded transition times and the TZ string footer, whose rule is in force in
+    /// the last era. The standard offsets, save amounts and formats are inferred (a TZfile does not record them), so they
+    /// are approximate: the 1944-1945 era of Paris below has a 1:00 standard offset, where tzdata has 0:00.
+    ///
+    /// ```rust
+    /// # let tzfile = if cfg!(windows) { "c:\\Users\\nbauw\\Dev\\zoneinfo\\Europe\\Paris" } else { "/usr/share/zoneinfo/Europe/Paris" };
+    /// use libtzfile::Tz;
+    /// let tz = Tz::new(tzfile).unwrap();
+    /// for era in tz.eras().unwrap() {
+    ///     println!("{:?}", era);
+    /// }
+    /// ```
+    ///
+    /// ```text
+    /// Era { start: None, end: Some(1891-03-15T23:50:39Z), std_offset: 561, dst_rules: false, save: 0, format: "LMT" }
+    /// Era { start: Some(1891-03-15T23:50:39Z), end: Some(1911-03-10T23:50:39Z), std_offset: 561, dst_rules: false, save: 0, format: "PMT" }
+    /// Era { start: Some(1911-03-10T23:50:39Z), end: Some(1940-06-14T22:00:00Z), std_offset: 0, dst_rules: true, save: 3600, format: "WE%sT" }
+    /// Era { start: Some(1940-06-14T22:00:00Z), end: Some(1944-08-24T22:00:00Z), std_offset: 3600, dst_rules: true, save: 3600, format: "CE%sT" }
+    /// Era { start: Some(1944-08-24T22:00:00Z), end: Some(1945-09-16T01:00:00Z), std_offset: 3600, dst_rules: true, save: 3600, format: "WE%sT" }
+    /// Era { start: Some(1945-09-16T01:00:00Z), end: None, std_offset: 3600, dst_rules: true, save: 3600, format: "CE%sT" }
+    /// ```
+    pub fn eras(&self) -> Result<Vec<Era>, TzError> {
+        eras::eras(self)
+    }
+
+    #[cfg(any(feature = "parse", feature = "json"))]
+    /// Returns the localized name of the zone at a given instant (ie. heure normale d'Europe centrale), from the CLDR
+    /// metaZones.xml file and a CLDR locale file, provided locally. The metazone in use at that instant gives the name,
+    /// unless the locale names the zone itself: a zone which changed of time zone gets the name it had at that instant.
+    /// Zones whose IANA name is not the canonical CLDR ID (ie. Asia/Kolkata, Asia/Calcutta for CLDR) need the aliases
+    /// of the CLDR bcp47/timezone.xml file, given with ```MetaZones::with_aliases```.
+    ///
+    /// ```rust
+    /// # let tzfile = if cfg!(windows) { "c:\\Users\\nbauw\\Dev\\zoneinfo\\Europe\\Paris" } else { "/usr/share/zoneinfo/Europe/Paris" };
+    /// use chrono::{TimeZone, Utc};
+    /// use libtzfile::{MetaZones, NameLength, TimeZoneNames, Tz};
+    /// let metazones = MetaZones::from_xml(
+    ///     "<supplementalData><metaZones><metazoneInfo><timezone type=\"Europe/Paris\">\
+    ///     <usesMetazone mzone=\"Europe_Central\"/></timezone></metazoneInfo></metaZones></supplementalData>",
+    /// )
+    /// .unwrap();
+    /// let names = TimeZoneNames::from_xml(
+    ///     "<ldml><dates><timeZoneNames><metazone type=\"Europe_Central\"><long>\
+    ///     <generic>heure d’Europe centrale</generic><standard>heure normale d’Europe centrale</standard>\
+    ///     <daylight>heure d’été d’Europe centrale</daylight></long></metazone></timeZoneNames></dates></ldml>",
+    /// )
+    /// .unwrap();
+    /// let tz = Tz::new(tzfile).unwrap();
+    /// let instant = Utc.with_ymd_and_hms(2025, 1, 15, 12, 0, 0).unwrap();
+    /// println!("{:?}", tz.display_name(&names, &metazones, instant, NameLength::Long));
+    /// ```
+    ///
+    /// ```text
+    /// Some("heure normale d’Europe centrale")
+    /// ```
+    pub fn display_name(
+        &self,
+        names: &TimeZoneNames,
+        metazones: &MetaZones,
+        instant: DateTime<Utc>,
+        length: NameLength,
+    ) -> Option<String> {
+        names::display_name(self, names, metazones, instant, length)
+    }
+
+    #[cfg(feature = "json")]
+    /// Returns the whole history of the zone (available with the json feature): recorded transition times with the offsets
+    /// before and after them, abbreviations, TZ string footer and leap seconds. It can be transformed to a json string.
+    ///
+    ///```rust
+    /// # let tzfile = if cfg!(windows) { "c:\\Users\\nbauw\\Dev\\zoneinfo\\America\\Phoenix" } else { "/usr/share/zoneinfo/America/Phoenix" };
+    /// use libtzfile::{Tz, TzError};
+    /// let history = Tz::new(tzfile)?.history().to_json()?;
+    /// println!("{}", history);
+    /// # Ok::<(), TzError>(())
+    ///```
+    ///
+    ///```text
+    /// {"timezone":"America/Phoenix","version":2,"transitions":[{"time":"1883-11-18T19:00:00Z","old_utc_offset":-26898,"new_utc_offset":-25200,"isdst":false,"abbreviation":"MST"},(...)],"abbreviations":["LMT","MDT","MST","MWT"],"footer":{"raw":"MST7","std_abbr":"MST","std_utoff":-25200,"dst":null},"leap_seconds":[]}
+    ///```
+    pub fn history(&self) -> ZoneHistory {
+        history::history(self)
+    }
+
     #[cfg(any(feature = "parse", feature = "json"))]
     /// Returns convenient data about a timezone for current date and time.
     /// ```rust
@@ -666,65 +1505,32 @@ impl Tz {
     /// Tzinfo { timezone: "Europe/Paris", utc_datetime: 2020-09-05T16:41:44.279502100Z, datetime: 2020-09-05T18:41:44.279502100+02:00, dst_from: Some(2020-03-29T01:00:00Z), dst_until: Some(2020-10-25T01:00:00Z), dst_period: true, raw_offset: 3600, dst_offset: 7200, utc_offset: +02:00, abbreviation: "CEST", week_number: 36 }
     /// ```
     pub fn zoneinfo(&self) -> Result<Tzinfo, TzError> {
-        let parsedtimechanges = match self.transition_times(Some(0)) {
-            Ok(p) => p,
-            Err(TzError::NoData) => Vec::new(),
-            Err(e) => return Err(e),
-        };
-        let d = Utc::now();
-        if parsedtimechanges.len() == 2 {
-            // 2 times changes the same year ? DST observed
-            // Are we in a dst period ? true / false
-            let dst = d > parsedtimechanges[0].time && d < parsedtimechanges[1].time;
-            let utc_offset = if dst == true {
-                FixedOffset::east_opt(parsedtimechanges[0].utc_offset as i32).unwrap()
-            } else {
-                FixedOffset::east_opt(parsedtimechanges[1].utc_offset as i32).unwrap()
-            };
-            Ok(Tzinfo {
-                timezone: (self.name).clone(),
-                week_number: d
-                    .with_timezone(&utc_offset)
-                    .format("%V")
-                    .to_string()
-                    .parse()?,
-                utc_datetime: d,
-                datetime: d.with_timezone(&utc_offset),
-                dst_from: Some(parsedtimechanges[0].time),
-                dst_until: Some(parsedtimechanges[1].time),
-                dst_period: dst,
-                raw_offset: parsedtimechanges[1].utc_offset,
-                dst_offset: parsedtimechanges[0].utc_offset,
-                utc_offset: utc_offset,
-                abbreviation: if dst == true {
-                    parsedtimechanges[0].abbreviation.clone()
-                } else {
-                    parsedtimechanges[1].abbreviation.clone()
-                },
-            })
-        } else if parsedtimechanges.len() == 1 {
-            let utc_offset = FixedOffset::east_opt(parsedtimechanges[0].utc_offset as i32).unwrap();
-            Ok(Tzinfo {
-                timezone: (self.name).clone(),
-                week_number: d
-                    .with_timezone(&utc_offset)
-                    .format("%V")
-                    .to_string()
-                    .parse()?,
-                utc_datetime: d,
-                datetime: d.with_timezone(&utc_offset),
-                dst_from: None,
-                dst_until: None,
-                dst_period: false,
-                raw_offset: parsedtimechanges[0].utc_offset,
-                dst_offset: 0,
-                utc_offset: utc_offset,
-                abbreviation: parsedtimechanges[0].abbreviation.clone(),
-            })
-        } else if parsedtimechanges.len() == 0 {
+        self.zoneinfo_with(&SystemClock)
+    }
+
+    #[cfg(any(feature = "parse", feature = "json"))]
+    /// Same as zoneinfo, the current date and time being given by a clock.
+    ///
+    /// ```rust
+    /// # let tzfile = if cfg!(windows) { "c:\\Users\\nbauw\\Dev\\zoneinfo\\Europe\\Paris" } else { "/usr/share/zoneinfo/Europe/Paris" };
+    /// use chrono::{TimeZone, Utc};
+    /// use libtzfile::Tz;
+    /// let now = Utc.with_ymd_and_hms(2020, 9, 5, 16, 41, 44).unwrap();
+    /// println!("{:?}", Tz::new(tzfile).unwrap().zoneinfo_with(&now).unwrap());
+    /// ```
+    ///
+    /// ```text
+    /// Tzinfo { timezone: "Europe/Paris", utc_datetime: 2020-09-05T16:41:44Z, datetime: 2020-09-05T18:41:44+02:00, dst_from: Some(2020-03-29T01:00:00Z), dst_until: Some(2020-10-25T01:00:00Z), dst_period: true, raw_offset: 3600, dst_offset: 7200, utc_offset: +02:00, abbreviation: "CEST", week_number: 36 }
+    /// ```
+    pub fn zoneinfo_with(&self, clock: &impl Clock) -> Result<Tzinfo, TzError> {
+        let d = clock.now();
+        if self.tzh_timecnt_data.is_empty()
+            && self.tz_footer.as_ref().is_none_or(|f| f.dst.is_none())
+        {
             // Addition for TZFiles that does NOT contain any transition time
-            let utc_offset = FixedOffset::east_opt(self.tzh_typecnt[0].tt_utoff as i32).unwrap();
-            Ok(Tzinfo {
+            let utc_offset = FixedOffset::east_opt(self.tzh_typecnt[0].tt_utoff as i32)
+                .ok_or(TzError::InvalidTimezone)?;
+            return Ok(Tzinfo {
                 timezone: (self.name).clone(),
                 week_number: d
                     .with_timezone(&utc_offset)
@@ -738,11 +1544,122 @@ impl Tz {
                 dst_period: false,
                 raw_offset: self.tzh_typecnt[0].tt_utoff,
                 dst_offset: 0,
-                utc_offset: utc_offset,
+                utc_offset,
                 abbreviation: (self.name).clone(),
-            })
+            });
+        }
+
+        // Local time type in effect, from the recorded transition times or the footer after the last one
+        let (offset, dst, abbreviation) = self.local_type_at(d.timestamp());
+        let year = d.year();
+        // Transitions after the instant are the ones at or after its next whole second
+        let after = |t: DateTime<Utc>| {
+            Utc.timestamp_opt(t.timestamp().saturating_add(1), 0)
+                .single()
+                .unwrap_or(t)
+        };
+        // Transition starting the DST period in effect before an instant: DST may be made of
+        // several transitions (ie. double summer time)
+        let dst_start =
+            |t: DateTime<Utc>| self.transitions_before(t).take_while(|tt| tt.isdst).last();
+        // Transition ending the DST period in effect after an instant
+        let dst_end = |t: DateTime<Utc>| self.transitions_from(t).find(|tt| !tt.isdst);
+
+        // DST period containing the instant, or else the one starting or ending this year.
+        // The DST period is told from the isdst flags of the surrounding transitions
+        // (in the southern hemisphere, DST ends first and starts again at the end of the year).
+        let (start, end) = if dst {
+            (dst_start(after(d)), dst_end(after(d)))
         } else {
-            Err(TzError::NoData)
+            let next = self
+                .transitions_from(after(d))
+                .take_while(|tt| tt.time.year() == year)
+                .find(|tt| tt.isdst);
+            match next {
+                Some(start) => {
+                    let end = dst_end(after(start.time));
+                    (Some(start), end)
+                }
+                None => {
+                    // The last DST period, if it ended this year
+                    let (mut last, mut end) = (None, None);
+                    for tt in self.transitions_before(after(d)) {
+                        if tt.isdst {
+                            last = Some(tt);
+                            break;
+                        }
+                        if tt.time.year() < year {
+                            break;
+                        }
+                        end = Some(tt);
+                    }
+                    match (last, end) {
+                        (Some(last), Some(end)) => (dst_start(after(last.time)), Some(end)),
+                        _ => (None, None),
+                    }
+                }
+            }
+        };
+
+        let (raw_offset, dst_offset) = match (&start, &end) {
+            (None, None) => (offset, 0),
+            // Normal offset: the one in force before DST started (or after it ended)
+            _ if dst => (
+                start
+                    .as_ref()
+                    .map(|s| s.old_utc_offset)
+                    .or(end.as_ref().map(|e| e.new_utc_offset))
+                    .unwrap_or(offset),
+                offset,
+            ),
+            _ => (offset, start.as_ref().map_or(offset, |s| s.new_utc_offset)),
+        };
+
+        let utc_offset = FixedOffset::east_opt(offset as i32).ok_or(TzError::InvalidTimezone)?;
+        Ok(Tzinfo {
+            timezone: (self.name).clone(),
+            week_number: d
+                .with_timezone(&utc_offset)
+                .format("%V")
+                .to_string()
+                .parse()?,
+            utc_datetime: d,
+            datetime: d.with_timezone(&utc_offset),
+            dst_from: start.map(|s| s.time),
+            dst_until: end.map(|e| e.time),
+            dst_period: dst,
+            raw_offset,
+            dst_offset,
+            utc_offset,
+            abbreviation: abbreviation.to_string(),
+        })
+    }
+
+    #[cfg(any(feature = "parse", feature = "json"))]
+    // Local time type (offset to UTC, isdst, abbreviation) in effect at a UTC timestamp,
+    // from the recorded transition times, or from the footer after the last one.
+    fn local_type_at(&self, t: i64) -> (isize, bool, &str) {
+        match (&self.tz_footer, self.tzh_timecnt_data.last()) {
+            (Some(footer), Some(&last)) if t > last => footer.ttinfo_at(t),
+            (Some(footer), None) => footer.ttinfo_at(t),
+            _ => {
+                let i = self.tzh_timecnt_data.partition_point(|&x| x <= t);
+                let ttinfo = self.ttinfo_at(i.checked_sub(1));
+                (
+                    ttinfo.tt_utoff,
+                    ttinfo.tt_isdst == 1,
+                    &self.tz_abbr[ttinfo.tt_abbrind as usize],
+                )
+            }
+        }
+    }
+
+    #[cfg(any(feature = "parse", feature = "json"))]
+    // Returns the local time type set by the transition at index i (None: before the first transition).
+    fn ttinfo_at(&self, i: Option<usize>) -> &Ttinfo {
+        match i {
+            Some(i) => &self.tzh_typecnt[self.tzh_timecnt_indices[i] as usize],
+            None => &self.tzh_typecnt[0],
         }
     }
 }
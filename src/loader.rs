@@ -0,0 +1,259 @@
+//! Loading of zones by name from a zoneinfo directory (available with the parse or json features).
+
+use crate::{String, ToString, Tz, TzError, Vec, WindowsZones, MAGIC};
+use chrono::{DateTime, Utc};
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Component, Path, PathBuf},
+    sync::OnceLock,
+};
+
+/// Loads zones by name (ie. Europe/Paris) from a zoneinfo directory, ```/usr/share/zoneinfo``` by default.
+///
+/// ```rust
+/// use libtzfile::ZoneLoader;
+/// let tz = ZoneLoader::default().load("America/Argentina/Buenos_Aires").unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct ZoneLoader {
+    root: PathBuf,
+    // Zones using each abbreviation, built on first use
+    abbreviations: OnceLock<BTreeMap<String, Vec<String>>>,
+    // Zones sharing each TZfile (ie. Asia/Kolkata and Asia/Calcutta), built on first use
+    links: OnceLock<BTreeMap<Vec<u8>, Vec<String>>>,
+}
+
+impl PartialEq for ZoneLoader {
+    fn eq(&self, other: &ZoneLoader) -> bool {
+        self.root == other.root
+    }
+}
+
+impl Eq for ZoneLoader {}
+
+impl Default for ZoneLoader {
+    fn default() -> ZoneLoader {
+        ZoneLoader::new("/usr/share/zoneinfo")
+    }
+}
+
+impl ZoneLoader {
+    /// Creates a loader reading the zones from a zoneinfo directory.
+    pub fn new(root: impl Into<PathBuf>) -> ZoneLoader {
+        ZoneLoader {
+            root: root.into(),
+            abbreviations: OnceLock::new(),
+            links: OnceLock::new(),
+        }
+    }
+
+    /// The zoneinfo directory.
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Loads a zone by name.
+    pub fn load(&self, name: &str) -> Result<Tz, TzError> {
+        Tz::from_bytes(&self.read(name)?, name)
+    }
+
+    /// Loads a zone by Windows time zone ID (ie. W. Europe Standard Time), using the IANA zone mapped to it in a territory
+    /// (or the default one).
+    ///
+    /// ```rust
+    /// use libtzfile::{WindowsZones, ZoneLoader};
+    /// let windows_zones = WindowsZones::bundled();
+    /// let tz = ZoneLoader::default()
+    ///     .load_windows(windows_zones, "Romance Standard Time", Some("FR"))
+    ///     .unwrap();
+    /// println!("{}", tz.zoneinfo().unwrap().timezone);
+    /// ```
+    ///
+    /// ```text
+    /// Europe/Paris
+    /// ```
+    pub fn load_windows(
+        &self,
+        windows_zones: &WindowsZones,
+        windows_id: &str,
+        territory: Option<&str>,
+    ) -> Result<Tz, TzError> {
+        let name = windows_zones
+            .to_iana(windows_id, territory)
+            .ok_or(TzError::InvalidTimezone)?;
+        self.load(name)
+    }
+
+    /// Returns the Windows time zone ID of a zone. Zones which are not in the mapping are compared with the mapped ones,
+    /// so that links (ie. Asia/Kolkata, mapped as Asia/Calcutta) are found.
+    pub fn windows_id<'a>(
+        &self,
+        windows_zones: &'a WindowsZones,
+        name: &str,
+    ) -> Result<Option<&'a str>, TzError> {
+        if let Some(windows_id) = windows_zones.to_windows(name) {
+            return Ok(Some(windows_id));
+        }
+        Ok(self
+            .links(name)?
+            .iter()
+            .find_map(|link| windows_zones.to_windows(link)))
+    }
+
+    /// Returns the names of all the zones found in the zoneinfo directory, sorted.
+    /// The ```posix``` and ```right``` sub-directories and the files which are not TZfiles are skipped.
+    pub fn zones(&self) -> Result<Vec<String>, TzError> {
+        let mut zones = Vec::new();
+        self.walk(&self.root, &mut zones)?;
+        zones.sort();
+        Ok(zones)
+    }
+
+    /// Returns the zone closest to a Tz (ie. one imported from a VTIMEZONE) between two instants: the one whose offset to UTC
+    /// differs for the shortest time, then whose abbreviation differs for the shortest time. On a tie, a zone named like
+    /// the Tz is preferred, then the first one in alphabetical order.
+    ///
+    /// ```rust
+    /// use chrono::{TimeZone, Utc};
+    /// use libtzfile::{Tz, ZoneLoader};
+    /// let tz = ZoneLoader::default().load("Asia/Kolkata").unwrap();
+    /// let from = Utc.with_ymd_and_hms(2000, 1, 1, 0, 0, 0).unwrap();
+    /// let until = Utc.with_ymd_and_hms(2030, 1, 1, 0, 0, 0).unwrap();
+    /// println!("{:?}", ZoneLoader::default().closest_zone(&tz, from, until).unwrap());
+    /// ```
+    ///
+    /// ```text
+    /// Some("Asia/Kolkata")
+    /// ```
+    pub fn closest_zone(
+        &self,
+        tz: &Tz,
+        from: DateTime<Utc>,
+        until: DateTime<Utc>,
+    ) -> Result<Option<String>, TzError> {
+        let mut closest: Option<((i64, i64, bool), String)> = None;
+        for name in self.zones()? {
+            // Some files of the directory may not be valid TZfiles
+            let zone = match self.load(&name) {
+                Ok(zone) => zone,
+                Err(_) => continue,
+            };
+            let (offset, abbreviation) = distance(tz, &zone, from, until);
+            let score = (offset, abbreviation, name != tz.name);
+            if closest.as_ref().is_none_or(|(best, _)| score < *best) {
+                closest = Some((score, name));
+            }
+        }
+        Ok(closest.map(|(_, name)| name))
+    }
+
+    // Names of the zones using an abbreviation in their TZfile or TZ string footer. The zoneinfo directory is read once,
+    // at the first call.
+    pub(crate) fn zones_using(&self, abbreviation: &str) -> Result<&[String], TzError> {
+        let index = match self.abbreviations.get() {
+            Some(index) => index,
+            None => {
+                let mut index: BTreeMap<String, Vec<String>> = BTreeMap::new();
+                for name in self.zones()? {
+                    // Some files of the directory may not be valid TZfiles
+                    let tz = match self.load(&name) {
+                        Ok(tz) => tz,
+                        Err(_) => continue,
+                    };
+                    let footer = tz.tz_footer.iter().flat_map(|f| {
+                        core::iter::once(&f.std_abbr).chain(f.dst.as_ref().map(|d| &d.abbr))
+                    });
+                    for abbr in tz.tz_abbr.iter().chain(footer) {
+                        let zones = index.entry(abbr.clone()).or_default();
+                        if zones.last() != Some(&name) {
+                            zones.push(name.clone());
+                        }
+                    }
+                }
+                self.abbreviations.get_or_init(|| index)
+            }
+        };
+        Ok(index.get(abbreviation).map_or(&[], Vec::as_slice))
+    }
+
+    // Names of the zones whose TZfile is the same as the one of a zone, the zone included if it is in the directory.
+    // The zoneinfo directory is read once, at the first call.
+    pub(crate) fn links(&self, name: &str) -> Result<&[String], TzError> {
+        let buf = self.read(name)?;
+        let index = match self.links.get() {
+            Some(index) => index,
+            None => {
+                let mut index: BTreeMap<Vec<u8>, Vec<String>> = BTreeMap::new();
+                for name in self.zones()? {
+                    if let Ok(buf) = self.read(&name) {
+                        index.entry(buf).or_default().push(name);
+                    }
+                }
+                self.links.get_or_init(|| index)
+            }
+        };
+        Ok(index.get(&buf).map_or(&[], Vec::as_slice))
+    }
+
+    fn read(&self, name: &str) -> Result<Vec<u8>, TzError> {
+        // Zone names are relative paths, which must stay in the zoneinfo directory
+        let path = Path::new(name);
+        if name.is_empty() || !path.components().all(|c| matches!(c, Component::Normal(_))) {
+            return Err(TzError::InvalidTimezone);
+        }
+        Ok(fs::read(self.root.join(path))?)
+    }
+
+    fn walk(&self, dir: &Path, zones: &mut Vec<String>) -> Result<(), TzError> {
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            let name = match path.strip_prefix(&self.root).ok().and_then(Path::to_str) {
+                Some(name) => name.replace('\\', "/"),
+                None => continue,
+            };
+            if path.is_dir() {
+                if name != "posix" && name != "right" {
+                    self.walk(&path, zones)?;
+                }
+            } else if name != "localtime" && name != "posixrules" && is_tzfile(&path) {
+                zones.push(name.to_string());
+            }
+        }
+        Ok(())
+    }
+}
+
+fn is_tzfile(path: &Path) -> bool {
+    use std::io::Read;
+    let mut magic = [0u8; 4];
+    fs::File::open(path)
+        .and_then(|mut f| f.read_exact(&mut magic))
+        .is_ok()
+        && u32::from_be_bytes(magic) == MAGIC
+}
+
+// Durations (in seconds) during which the offsets to UTC, and the abbreviations, of two zones differ between two instants
+fn distance(a: &Tz, b: &Tz, from: DateTime<Utc>, until: DateTime<Utc>) -> (i64, i64) {
+    let mut instants: Vec<DateTime<Utc>> = a
+        .transitions_between(from, until)
+        .into_iter()
+        .chain(b.transitions_between(from, until))
+        .map(|tt| tt.time)
+        .chain([from, until])
+        .collect();
+    instants.sort_unstable();
+    instants.dedup();
+    let (mut offset, mut abbreviation) = (0, 0);
+    for period in instants.windows(2) {
+        let (la, lb) = (a.lookup(period[0]), b.lookup(period[0]));
+        let duration = (period[1] - period[0]).num_seconds();
+        if la.utc_offset != lb.utc_offset {
+            offset += duration;
+        }
+        if la.abbreviation != lb.abbreviation {
+            abbreviation += duration;
+        }
+    }
+    (offset, abbreviation)
+}
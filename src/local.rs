@@ -0,0 +1,118 @@
+//! Conversions between UTC and local times (available with the parse or json features).
+
+use crate::{String, Tz, TzError};
+use chrono::{DateTime, NaiveDateTime, TimeZone, Utc};
+
+/// Local time type in effect at an instant, as returned by ```Tz::lookup```.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "python",
+    pyo3::pyclass(module = "libtzfile", get_all, frozen, skip_from_py_object)
+)]
+pub struct LocalTimeType {
+    /// Offset to UTC, in seconds
+    pub utc_offset: isize,
+    /// Is DST observed ?
+    pub isdst: bool,
+    /// TZ abbreviation
+    pub abbreviation: String,
+}
+
+/// How a local time is mapped to UTC, as returned by ```Tz::local_mapping```.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+pub enum LocalMapping {
+    /// The local time maps to a single instant
+    Single(DateTime<Utc>),
+    /// The local time occurs twice (ie. when DST ends): earlier and later instants
+    Ambiguous(DateTime<Utc>, DateTime<Utc>),
+    /// The local time is skipped (ie. when DST starts). The instants are computed with the offset
+    /// after the transition (earlier) and before the transition (later).
+    Gap(DateTime<Utc>, DateTime<Utc>),
+}
+
+/// Policy used by ```Tz::local_to_utc``` for ambiguous or skipped local times.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "wasm", wasm_bindgen::prelude::wasm_bindgen)]
+pub enum Disambiguation {
+    /// Earlier instant for ambiguous local times, later instant for skipped local times
+    #[default]
+    Compatible,
+    /// Earlier instant
+    Earlier,
+    /// Later instant
+    Later,
+    /// Ambiguous or skipped local times are errors
+    Reject,
+}
+
+impl LocalMapping {
+    /// Picks an instant according to a disambiguation policy.
+    pub fn resolve(self, disambiguation: Disambiguation) -> Result<DateTime<Utc>, TzError> {
+        match (self, disambiguation) {
+            (LocalMapping::Single(t), _) => Ok(t),
+            (LocalMapping::Ambiguous(_, _), Disambiguation::Reject) => {
+                Err(TzError::AmbiguousLocalTime)
+            }
+            (LocalMapping::Gap(_, _), Disambiguation::Reject) => Err(TzError::SkippedLocalTime),
+            (LocalMapping::Ambiguous(earlier, _), Disambiguation::Compatible)
+            | (LocalMapping::Ambiguous(earlier, _), Disambiguation::Earlier)
+            | (LocalMapping::Gap(earlier, _), Disambiguation::Earlier) => Ok(earlier),
+            (LocalMapping::Ambiguous(_, later), Disambiguation::Later)
+            | (LocalMapping::Gap(_, later), Disambiguation::Later)
+            | (LocalMapping::Gap(_, later), Disambiguation::Compatible) => Ok(later),
+        }
+    }
+}
+
+// Largest change of offset to UTC ever seen is about a day (ie. Pacific/Kwajalein, Pacific/Apia):
+// the transitions which may affect a local time are within two days of it.
+const WINDOW: i64 = 2 * 86400;
+
+pub(crate) fn local_mapping(tz: &Tz, local: NaiveDateTime) -> Result<LocalMapping, TzError> {
+    // Transitions happen at whole seconds: the fraction is only carried into the instants
+    let nanos = local.and_utc().timestamp_subsec_nanos();
+    let local = local.and_utc().timestamp();
+    let utc = |t: i64| Utc.timestamp_opt(t, nanos).single().ok_or(TzError::NoData);
+    let from = utc(local - WINDOW)?;
+    let transitions: crate::Vec<_> = tz
+        .transitions_from(from)
+        .take_while(|tt| tt.time.timestamp() < local + WINDOW)
+        .collect();
+
+    // Candidate offsets are the ones in effect around the local time
+    let mut offsets: crate::Vec<isize> = transitions
+        .iter()
+        .flat_map(|tt| [tt.old_utc_offset, tt.new_utc_offset])
+        .collect();
+    offsets.push(tz.local_type_at(local).0);
+    offsets.sort_unstable();
+    offsets.dedup();
+    let mut instants: crate::Vec<i64> = offsets
+        .iter()
+        .map(|&o| local - o as i64)
+        .filter(|&t| tz.local_type_at(t).0 as i64 == local - t)
+        .collect();
+    instants.sort_unstable();
+
+    match instants[..] {
+        [t] => Ok(LocalMapping::Single(utc(t)?)),
+        [earlier, .., later] => Ok(LocalMapping::Ambiguous(utc(earlier)?, utc(later)?)),
+        [] => {
+            // Skipped by a transition moving local time forward
+            let tt = transitions
+                .iter()
+                .find(|tt| {
+                    let t = tt.time.timestamp();
+                    t + tt.old_utc_offset as i64 <= local && local < t + tt.new_utc_offset as i64
+                })
+                .ok_or(TzError::NoData)?;
+            Ok(LocalMapping::Gap(
+                utc(local - tt.new_utc_offset as i64)?,
+                utc(local - tt.old_utc_offset as i64)?,
+            ))
+        }
+    }
+}
@@ -0,0 +1,233 @@
+//! Localized zone names, from the CLDR metaZones.xml and locale (timeZoneNames) files (available with the parse or json features).
+
+use crate::{xml, String, ToString, Tz, TzError, Vec};
+use chrono::{DateTime, NaiveDateTime, Utc};
+use std::{fs, path::Path};
+
+/// Length of a zone name: long (ie. Central European Summer Time) or short (ie. CEST, which most locales only give for a few zones).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NameLength {
+    /// Long name
+    Long,
+    /// Short name
+    Short,
+}
+
+/// A metazone (ie. Europe_Central) used by a zone during a period.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MetaZonePeriod {
+    /// Metazone identifier
+    pub metazone: String,
+    /// Start of the period (included), None since the beginning
+    pub from: Option<DateTime<Utc>>,
+    /// End of the period (excluded), None if still in use
+    pub to: Option<DateTime<Utc>>,
+}
+
+/// Metazones used by each zone over time, as given by the CLDR metaZones.xml file: zones sharing their names
+/// (ie. Europe/Paris and Europe/Berlin) use the same metazone, and a zone changes metazone when it changes of time zone.
+///
+/// CLDR names the zones by their canonical CLDR IDs, which may be former IANA names (ie. Asia/Calcutta for Asia/Kolkata):
+/// the aliases of the CLDR bcp47/timezone.xml file, given with ```with_aliases```, map the other names to them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MetaZones {
+    zones: Vec<(String, Vec<MetaZonePeriod>)>,
+    // Names of each zone, the canonical CLDR ID first
+    aliases: Vec<Vec<String>>,
+}
+
+impl MetaZones {
+    /// Reads the metazones from the content of a metaZones.xml file.
+    pub fn from_xml(xml: &str) -> Result<MetaZones, TzError> {
+        let mut zones: Vec<(String, Vec<MetaZonePeriod>)> = Vec::new();
+        for event in xml::parse(xml)? {
+            match event {
+                xml::Event::Start("timezone", _) => {
+                    let zone = event.attribute("type").ok_or(TzError::ParseError)?;
+                    zones.push((zone.to_string(), Vec::new()));
+                }
+                xml::Event::Start("usesMetazone", _) => {
+                    let periods = &mut zones.last_mut().ok_or(TzError::ParseError)?.1;
+                    periods.push(MetaZonePeriod {
+                        metazone: event
+                            .attribute("mzone")
+                            .ok_or(TzError::ParseError)?
+                            .to_string(),
+                        from: event.attribute("from").map(datetime).transpose()?,
+                        to: event.attribute("to").map(datetime).transpose()?,
+                    });
+                }
+                _ => (),
+            }
+        }
+        if zones.is_empty() {
+            return Err(TzError::NoData);
+        }
+        Ok(MetaZones {
+            zones,
+            aliases: Vec::new(),
+        })
+    }
+
+    /// Reads the metazones from a metaZones.xml file (ie. from a CLDR release).
+    pub fn from_file(path: impl AsRef<Path>) -> Result<MetaZones, TzError> {
+        MetaZones::from_xml(&fs::read_to_string(path)?)
+    }
+
+    /// Adds the zone aliases from the content of a CLDR bcp47/timezone.xml file.
+    pub fn with_aliases(mut self, xml: &str) -> Result<MetaZones, TzError> {
+        for event in xml::parse(xml)? {
+            if !matches!(event, xml::Event::Start("type", _)) {
+                continue;
+            }
+            // Deprecated and unknown zones have no aliases
+            if let Some(alias) = event.attribute("alias") {
+                self.aliases
+                    .push(alias.split_whitespace().map(|z| z.to_string()).collect());
+            }
+        }
+        if self.aliases.is_empty() {
+            return Err(TzError::NoData);
+        }
+        Ok(self)
+    }
+
+    /// Adds the zone aliases from a CLDR bcp47/timezone.xml file.
+    pub fn with_aliases_file(self, path: impl AsRef<Path>) -> Result<MetaZones, TzError> {
+        self.with_aliases(&fs::read_to_string(path)?)
+    }
+
+    /// Returns the canonical CLDR ID of a zone (ie. Asia/Calcutta for Asia/Kolkata), or the zone itself if it has no alias.
+    pub fn canonical<'a>(&'a self, zone: &'a str) -> &'a str {
+        self.aliases
+            .iter()
+            .find(|names| names.iter().any(|z| z == zone))
+            .and_then(|names| names.first())
+            .map_or(zone, |z| z.as_str())
+    }
+
+    /// Metazones used by a zone, in chronological order. The zone is looked up by its canonical CLDR ID.
+    pub fn periods(&self, zone: &str) -> &[MetaZonePeriod] {
+        let zone = self.canonical(zone);
+        self.zones
+            .iter()
+            .find(|(z, _)| z == zone)
+            .map_or(&[], |(_, periods)| periods)
+    }
+
+    /// Returns the metazone used by a zone at a given instant.
+    pub fn metazone(&self, zone: &str, instant: DateTime<Utc>) -> Option<&str> {
+        self.periods(zone)
+            .iter()
+            .find(|p| p.from.is_none_or(|f| f <= instant) && p.to.is_none_or(|t| instant < t))
+            .map(|p| p.metazone.as_str())
+    }
+}
+
+// yyyy-MM-dd HH:mm, in UTC
+fn datetime(s: &str) -> Result<DateTime<Utc>, TzError> {
+    NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M")
+        .map(|d| d.and_utc())
+        .map_err(|_| TzError::ParseError)
+}
+
+// Generic, standard and daylight names
+type Names = [Option<String>; 3];
+
+/// Zone names of a locale, as given by the timeZoneNames element of a CLDR locale file (ie. common/main/fr.xml).
+/// Names given for a zone (ie. British Summer Time for Europe/London) take precedence over the ones of its metazone.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TimeZoneNames {
+    // Zone or metazone identifier, is it a metazone, length and names
+    names: Vec<(String, bool, NameLength, Names)>,
+}
+
+impl TimeZoneNames {
+    /// Reads the zone names from the content of a CLDR locale file. Only the names of this file are read: the ones
+    /// inherited from a parent locale (ie. fr for fr_CA) need the parent's file.
+    pub fn from_xml(xml: &str) -> Result<TimeZoneNames, TzError> {
+        let mut names: Vec<(String, bool, NameLength, Names)> = Vec::new();
+        let mut owner: Option<(String, bool)> = None;
+        let mut length = None;
+        let mut field = None;
+        for event in xml::parse(xml)? {
+            match event {
+                xml::Event::Start(element @ ("zone" | "metazone"), _) => {
+                    let id = event.attribute("type").ok_or(TzError::ParseError)?;
+                    owner = Some((id.to_string(), element == "metazone"));
+                }
+                xml::Event::Start("long", _) => length = Some(NameLength::Long),
+                xml::Event::Start("short", _) => length = Some(NameLength::Short),
+                // Alternative names (ie. formal) are skipped
+                xml::Event::Start(element, ref attributes) if attributes.is_empty() => {
+                    field = ["generic", "standard", "daylight"]
+                        .iter()
+                        .position(|f| *f == element);
+                }
+                // Inheritance marker: the name is the parent locale's
+                xml::Event::Text(text) if text == "↑↑↑" => (),
+                xml::Event::Text(text) => {
+                    if let (Some((id, metazone)), Some(length), Some(field)) =
+                        (&owner, length, field)
+                    {
+                        let i = match names
+                            .iter()
+                            .position(|(n, m, l, _)| n == id && m == metazone && *l == length)
+                        {
+                            Some(i) => i,
+                            None => {
+                                names.push((id.clone(), *metazone, length, Names::default()));
+                                names.len() - 1
+                            }
+                        };
+                        names[i].3[field] = Some(text);
+                    }
+                }
+                xml::Event::End("zone" | "metazone") => owner = None,
+                xml::Event::End("long" | "short") => length = None,
+                xml::Event::End(_) | xml::Event::Start(..) => field = None,
+            }
+        }
+        Ok(TimeZoneNames { names })
+    }
+
+    /// Reads the zone names from a CLDR locale file.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<TimeZoneNames, TzError> {
+        TimeZoneNames::from_xml(&fs::read_to_string(path)?)
+    }
+
+    /// Returns the name of a zone at a given instant, standard or daylight, using the metazone in use at that instant.
+    /// Without a standard name, the generic one is returned. The zone is looked up by its canonical CLDR ID.
+    pub fn display_name(
+        &self,
+        metazones: &MetaZones,
+        zone: &str,
+        instant: DateTime<Utc>,
+        isdst: bool,
+        length: NameLength,
+    ) -> Option<&str> {
+        let fields: &[usize] = if isdst { &[2] } else { &[1, 0] };
+        let name = |id: &str, metazone: bool| {
+            let names = self
+                .names
+                .iter()
+                .find(|(n, m, l, _)| n == id && *m == metazone && *l == length)?;
+            fields.iter().find_map(|&f| names.3[f].as_deref())
+        };
+        name(metazones.canonical(zone), false)
+            .or_else(|| name(metazones.metazone(zone, instant)?, true))
+    }
+}
+
+pub(crate) fn display_name(
+    tz: &Tz,
+    names: &TimeZoneNames,
+    metazones: &MetaZones,
+    instant: DateTime<Utc>,
+    length: NameLength,
+) -> Option<String> {
+    let isdst = tz.lookup(instant).isdst;
+    names
+        .display_name(metazones, &tz.name, instant, isdst, length)
+        .map(|n| n.to_string())
+}
@@ -0,0 +1,170 @@
+//! Parsing of date and time strings containing an IANA zone name or a TZ abbreviation (available with the parse or json features).
+
+use crate::{Disambiguation, String, ToString, Tz, TzError, Vec, ZoneLoader};
+use chrono::{DateTime, FixedOffset, NaiveDateTime, Utc};
+
+/// A date and time resolved in a zone.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+pub struct ZonedDateTime {
+    /// UTC date and time
+    pub utc: DateTime<Utc>,
+    /// Local date and time, with its offset to UTC
+    pub local: DateTime<FixedOffset>,
+    /// IANA zone name, None when given by an abbreviation shared by several zones with the same offset
+    pub zone: Option<String>,
+    /// TZ abbreviation in effect
+    pub abbreviation: String,
+}
+
+// Date and time layouts accepted around the zone token
+const LAYOUTS: [&str; 8] = [
+    "%Y-%m-%d %H:%M:%S%.f",
+    "%Y-%m-%dT%H:%M:%S%.f",
+    "%Y-%m-%d %H:%M",
+    "%Y-%m-%dT%H:%M",
+    "%a %b %d %H:%M:%S%.f %Y",
+    "%b %d %H:%M:%S%.f %Y",
+    "%a %d %b %Y %H:%M:%S%.f",
+    "%d %b %Y %H:%M:%S%.f",
+];
+
+enum ZoneToken<'a> {
+    Name(&'a str),
+    Abbreviation(&'a str),
+}
+
+impl ZoneLoader {
+    /// Parses a date and time containing an IANA zone name (```2024-03-10 02:30:00 America/New_York```)
+    /// or a TZ abbreviation (```Sun Mar 10 02:30:00 EST 2024```).
+    ///
+    /// With a zone name, the local time is converted to UTC according to the disambiguation policy.
+    /// An abbreviation gives the offset to UTC: it is looked for in the preferred zones first, then in all the zones
+    /// of the loader using it (the zoneinfo directory is indexed once per loader, at the first such lookup).
+    /// An abbreviation used with different offsets by several zones (ie. IST) is an error,
+    /// unless it is used by one of the preferred zones.
+    ///
+    /// ```rust
+    /// use libtzfile::{Disambiguation, ZoneLoader};
+    /// let loader = ZoneLoader::default();
+    /// let parsed = loader
+    ///     .parse_datetime("Sun Mar 10 01:30:00 CST 2024", Disambiguation::Reject, &["America/Chicago"])
+    ///     .unwrap();
+    /// println!("{} {:?}", parsed.utc, parsed.zone);
+    /// ```
+    ///
+    /// ```text
+    /// 2024-03-10 07:30:00 UTC Some("America/Chicago")
+    /// ```
+    pub fn parse_datetime(
+        &self,
+        s: &str,
+        disambiguation: Disambiguation,
+        preferred_zones: &[&str],
+    ) -> Result<ZonedDateTime, TzError> {
+        let tokens: Vec<&str> = s.split_whitespace().collect();
+        let (position, zone) = tokens
+            .iter()
+            .enumerate()
+            .find_map(|(i, token)| zone_token(token).map(|zone| (i, zone)))
+            .ok_or(TzError::InvalidTimezone)?;
+        let mut rest = tokens.clone();
+        rest.remove(position);
+        let local = parse_local(&rest.join(" "))?;
+
+        match zone {
+            ZoneToken::Name(name) => {
+                let tz = self.load(name)?;
+                let utc = tz.local_to_utc(local, disambiguation)?;
+                let ltt = tz.lookup(utc);
+                zoned(
+                    utc,
+                    ltt.utc_offset,
+                    Some(name.to_string()),
+                    ltt.abbreviation,
+                )
+            }
+            ZoneToken::Abbreviation(abbr) => {
+                for name in preferred_zones {
+                    let tz = self.load(name)?;
+                    if let Some(offset) = abbreviation_offset(&tz, abbr, local) {
+                        let utc = local.and_utc() - chrono::Duration::seconds(offset as i64);
+                        return zoned(utc, offset, Some(name.to_string()), abbr.to_string());
+                    }
+                }
+                // Zones using the abbreviation, and their offsets
+                let mut matches: Vec<(String, isize)> = Vec::new();
+                for name in self.zones_using(abbr)? {
+                    let tz = self.load(name)?;
+                    if let Some(offset) = abbreviation_offset(&tz, abbr, local) {
+                        matches.push((name.clone(), offset));
+                    }
+                }
+                let offset = match matches.first() {
+                    Some(&(_, offset)) => offset,
+                    None => return Err(TzError::InvalidTimezone),
+                };
+                if matches.iter().any(|&(_, o)| o != offset) {
+                    return Err(TzError::AmbiguousAbbreviation);
+                }
+                let zone = match &matches[..] {
+                    [(name, _)] => Some(name.clone()),
+                    _ => None,
+                };
+                let utc = local.and_utc() - chrono::Duration::seconds(offset as i64);
+                zoned(utc, offset, zone, abbr.to_string())
+            }
+        }
+    }
+}
+
+pub(crate) fn zoned(
+    utc: DateTime<Utc>,
+    utc_offset: isize,
+    zone: Option<String>,
+    abbreviation: String,
+) -> Result<ZonedDateTime, TzError> {
+    let offset = FixedOffset::east_opt(utc_offset as i32).ok_or(TzError::InvalidTimezone)?;
+    Ok(ZonedDateTime {
+        utc,
+        local: utc.with_timezone(&offset),
+        zone,
+        abbreviation,
+    })
+}
+
+// Zone names contain a slash (ie. Europe/Paris), abbreviations are 3 to 6 capital letters (ie. CEST)
+fn zone_token(token: &str) -> Option<ZoneToken<'_>> {
+    let first = token.chars().next()?;
+    if token.contains('/') && first.is_ascii_uppercase() {
+        Some(ZoneToken::Name(token))
+    } else if (3..=6).contains(&token.len()) && token.chars().all(|c| c.is_ascii_uppercase()) {
+        Some(ZoneToken::Abbreviation(token))
+    } else {
+        None
+    }
+}
+
+fn parse_local(s: &str) -> Result<NaiveDateTime, TzError> {
+    LAYOUTS
+        .iter()
+        .find_map(|layout| NaiveDateTime::parse_from_str(s, layout).ok())
+        .ok_or(TzError::ParseError)
+}
+
+// Offset to UTC of a zone's local time type with the given abbreviation, in effect around a local time
+fn abbreviation_offset(tz: &Tz, abbr: &str, local: NaiveDateTime) -> Option<isize> {
+    let local = local.and_utc();
+    let from = local - chrono::Duration::days(2);
+    let until = local + chrono::Duration::days(2);
+    let mut instants = Vec::from([from, until]);
+    for tt in tz.transitions_from(from).take_while(|tt| tt.time < until) {
+        instants.push(tt.time - chrono::Duration::seconds(1));
+        instants.push(tt.time);
+    }
+    instants
+        .into_iter()
+        .map(|t| tz.lookup(t))
+        .find(|ltt| ltt.abbreviation == abbr)
+        .map(|ltt| ltt.utc_offset)
+}
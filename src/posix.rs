@@ -0,0 +1,472 @@
+//! Parsing of the TZ string footer found at the end of version 2+ TZfiles (see tzfile(5) and the POSIX TZ environment variable).
+//! The footer describes the local time types to use after the last transition time of the file.
+
+use crate::{String, ToString, TzError};
+
+/// Parsed TZ string footer, which is the zone's rule after the last transition time recorded in the TZfile.
+///
+/// Offsets are given in seconds east of UTC, like ```tt_utoff``` (the TZ string itself uses the opposite sign).
+/// With the json feature enabled, deserialization checks that the fields match the TZ string.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "json", serde(try_from = "RawPosixTz"))]
+pub struct PosixTz {
+    /// The TZ string, as found in the TZfile
+    pub raw: String,
+    /// Standard time abbreviation
+    pub std_abbr: String,
+    /// Standard time offset to UTC, in seconds
+    pub std_utoff: isize,
+    /// Daylight saving time rule, if DST is observed
+    pub dst: Option<PosixDst>,
+}
+
+// Deserialized PosixTz fields, before the checks
+#[cfg(feature = "json")]
+#[derive(serde::Deserialize)]
+struct RawPosixTz {
+    raw: String,
+    std_abbr: String,
+    std_utoff: isize,
+    dst: Option<PosixDst>,
+}
+
+#[cfg(feature = "json")]
+impl TryFrom<RawPosixTz> for PosixTz {
+    type Error = TzError;
+
+    fn try_from(raw: RawPosixTz) -> Result<PosixTz, TzError> {
+        let tz = PosixTz {
+            raw: raw.raw,
+            std_abbr: raw.std_abbr,
+            std_utoff: raw.std_utoff,
+            dst: raw.dst,
+        };
+        if tz.is_consistent() {
+            Ok(tz)
+        } else {
+            Err(TzError::ParseError)
+        }
+    }
+}
+
+/// Daylight saving time part of a TZ string footer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+pub struct PosixDst {
+    /// DST abbreviation
+    pub abbr: String,
+    /// DST offset to UTC, in seconds
+    pub utoff: isize,
+    /// Day DST starts
+    pub start: PosixDate,
+    /// Local (standard) time of the day DST starts, in seconds. May be negative or exceed 24 hours.
+    pub start_time: isize,
+    /// Day DST ends
+    pub end: PosixDate,
+    /// Local (daylight saving) time of the day DST ends, in seconds. May be negative or exceed 24 hours.
+    pub end_time: isize,
+}
+
+/// Day of the year a DST rule applies.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+pub enum PosixDate {
+    /// ```Jn```: Julian day (1 to 365), February 29th is never counted
+    Julian(u16),
+    /// ```n```: zero-based day of year (0 to 365), February 29th is counted in leap years
+    ZeroBased(u16),
+    /// ```Mm.w.d```: day ```d``` (0 is Sunday) of week ```w``` (1 to 5, 5 is the last one) of month ```m```
+    MonthWeekDay { month: u8, week: u8, weekday: u8 },
+}
+
+impl PosixTz {
+    /// Parses a TZ string, ie. ```CET-1CEST,M3.5.0,M10.5.0/3```
+    pub fn parse(tz: &str) -> Result<PosixTz, TzError> {
+        let mut p = Parser {
+            s: tz.as_bytes(),
+            pos: 0,
+        };
+        let std_abbr = p.abbr()?;
+        let std_utoff = -p.hms()?;
+        let dst = if p.done() {
+            None
+        } else {
+            let abbr = p.abbr()?;
+            let utoff = if p.done() || p.peek() == Some(b',') {
+                std_utoff + 3600
+            } else {
+                -p.hms()?
+            };
+            p.expect(b',')?;
+            let start = p.date()?;
+            let start_time = p.time()?;
+            p.expect(b',')?;
+            let end = p.date()?;
+            let end_time = p.time()?;
+            Some(PosixDst {
+                abbr,
+                utoff,
+                start,
+                start_time,
+                end,
+                end_time,
+            })
+        };
+        if !p.done() {
+            return Err(TzError::ParseError);
+        }
+        Ok(PosixTz {
+            raw: tz.to_string(),
+            std_abbr,
+            std_utoff,
+            dst,
+        })
+    }
+
+    // Checks that the fields are the ones of the TZ string
+    pub(crate) fn is_consistent(&self) -> bool {
+        PosixTz::parse(&self.raw).as_ref() == Ok(self)
+    }
+
+    #[cfg(any(feature = "parse", feature = "json"))]
+    // Builds a rule, writing its TZ string (ie. CET-1CEST,M3.5.0,M10.5.0/3)
+    pub(crate) fn new(std_abbr: String, std_utoff: isize, dst: Option<PosixDst>) -> PosixTz {
+        use std::format;
+        let abbr = |a: &str| {
+            if a.bytes().all(|c| c.is_ascii_alphabetic()) {
+                a.to_string()
+            } else {
+                format!("<{a}>")
+            }
+        };
+        let hms = |t: isize| {
+            let sign = if t < 0 { "-" } else { "" };
+            let (h, m, s) = (t.abs() / 3600, t.abs() / 60 % 60, t.abs() % 60);
+            match (m, s) {
+                (0, 0) => format!("{sign}{h}"),
+                (_, 0) => format!("{sign}{h}:{m:02}"),
+                _ => format!("{sign}{h}:{m:02}:{s:02}"),
+            }
+        };
+        let date = |d: PosixDate, time: isize| {
+            let d = match d {
+                PosixDate::Julian(n) => format!("J{n}"),
+                PosixDate::ZeroBased(n) => format!("{n}"),
+                PosixDate::MonthWeekDay {
+                    month,
+                    week,
+                    weekday,
+                } => format!("M{month}.{week}.{weekday}"),
+            };
+            // 02:00 is the default time
+            if time == 7200 {
+                d
+            } else {
+                format!("{d}/{}", hms(time))
+            }
+        };
+        let mut raw = format!("{}{}", abbr(&std_abbr), hms(-std_utoff));
+        if let Some(dst) = &dst {
+            raw.push_str(&abbr(&dst.abbr));
+            if dst.utoff != std_utoff + 3600 {
+                raw.push_str(&hms(-dst.utoff));
+            }
+            raw.push_str(&format!(
+                ",{},{}",
+                date(dst.start, dst.start_time),
+                date(dst.end, dst.end_time)
+            ));
+        }
+        PosixTz {
+            raw,
+            std_abbr,
+            std_utoff,
+            dst,
+        }
+    }
+
+    // Parses the footer bytes following the v2 data block ("\n<TZ string>\n").
+    // Returns None when there is no footer, or when it is empty.
+    pub(crate) fn from_footer(buffer: &[u8]) -> Result<Option<PosixTz>, TzError> {
+        let footer = match buffer.strip_prefix(b"\n") {
+            Some(f) => f,
+            None => return Ok(None),
+        };
+        let end = footer
+            .iter()
+            .position(|&c| c == b'\n')
+            .ok_or(TzError::ParseError)?;
+        let tz = core::str::from_utf8(&footer[..end]).map_err(|_| TzError::BadUtf8String)?;
+        if tz.is_empty() {
+            return Ok(None);
+        }
+        PosixTz::parse(tz).map(Some)
+    }
+
+    /// Returns the DST transitions of a year as (UTC timestamp, isdst after the transition), sorted by time.
+    /// Returns no transition when DST is not observed, or observed all year long.
+    pub fn transitions(&self, year: i32) -> [Option<(i64, bool)>; 2] {
+        let dst = match &self.dst {
+            Some(dst) => dst,
+            None => return [None, None],
+        };
+        let start = dst.start_utc(year, self.std_utoff);
+        let end = dst.end_utc(year);
+        // zic encodes permanent DST as a DST period ending when the next one starts
+        if end == dst.start_utc(year + 1, self.std_utoff) || dst.end_utc(year - 1) == start {
+            return [None, None];
+        }
+        if start < end {
+            [Some((start, true)), Some((end, false))]
+        } else {
+            [Some((end, false)), Some((start, true))]
+        }
+    }
+
+    /// Returns the local time type (offset to UTC, isdst, abbreviation) in effect at a UTC timestamp.
+    pub fn ttinfo_at(&self, t: i64) -> (isize, bool, &str) {
+        if self.dst.is_none() {
+            return self.ttinfo(false);
+        }
+        let year = year_of(t);
+        let isdst = match self.transitions(year) {
+            [Some((t0, d0)), Some((t1, d1))] => {
+                if t < t0 {
+                    !d0
+                } else if t < t1 {
+                    d0
+                } else {
+                    d1
+                }
+            }
+            // DST all year long
+            _ => true,
+        };
+        self.ttinfo(isdst)
+    }
+
+    // Local time type (offset to UTC, isdst, abbreviation) of the standard or DST part of the rule
+    pub(crate) fn ttinfo(&self, isdst: bool) -> (isize, bool, &str) {
+        match &self.dst {
+            Some(dst) if isdst => (dst.utoff, true, &dst.abbr),
+            _ => (self.std_utoff, false, &self.std_abbr),
+        }
+    }
+}
+
+impl PosixDst {
+    fn start_utc(&self, year: i32, std_utoff: isize) -> i64 {
+        self.start.days(year) * 86400 + (self.start_time - std_utoff) as i64
+    }
+
+    fn end_utc(&self, year: i32) -> i64 {
+        self.end.days(year) * 86400 + (self.end_time - self.utoff) as i64
+    }
+}
+
+impl PosixDate {
+    // Days since the Unix epoch of the rule's day for the given year
+    fn days(&self, year: i32) -> i64 {
+        let jan1 = days_from_civil(year, 1, 1);
+        match *self {
+            PosixDate::Julian(n) => {
+                let n = n as i64;
+                jan1 + n - 1 + if is_leap(year) && n >= 60 { 1 } else { 0 }
+            }
+            PosixDate::ZeroBased(n) => jan1 + n as i64,
+            PosixDate::MonthWeekDay {
+                month,
+                week,
+                weekday,
+            } => {
+                let first = days_from_civil(year, month as u32, 1);
+                // 1970-01-01 was a Thursday
+                let first_weekday = (first + 4).rem_euclid(7);
+                let mut day =
+                    (weekday as i64 - first_weekday).rem_euclid(7) + (week as i64 - 1) * 7;
+                while day >= days_in_month(year, month as u32) {
+                    day -= 7;
+                }
+                first + day
+            }
+        }
+    }
+}
+
+// Days since the Unix epoch of a proleptic Gregorian date (H. Hinnant's algorithm)
+pub(crate) fn days_from_civil(year: i32, month: u32, day: u32) -> i64 {
+    let y = year as i64 - if month <= 2 { 1 } else { 0 };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let m = month as i64;
+    let doy = (153 * (if m > 2 { m - 3 } else { m + 9 }) + 2) / 5 + day as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146097 + doe - 719468
+}
+
+// Year of a UTC timestamp
+pub(crate) fn year_of(t: i64) -> i32 {
+    let z = t.div_euclid(86400) + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    (yoe + era * 400 + if mp >= 10 { 1 } else { 0 }) as i32
+}
+
+fn is_leap(year: i32) -> bool {
+    year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+}
+
+pub(crate) fn days_in_month(year: i32, month: u32) -> i64 {
+    match month {
+        2 if is_leap(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+struct Parser<'a> {
+    s: &'a [u8],
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn done(&self) -> bool {
+        self.pos == self.s.len()
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.s.get(self.pos).copied()
+    }
+
+    fn expect(&mut self, c: u8) -> Result<(), TzError> {
+        if self.peek() != Some(c) {
+            return Err(TzError::ParseError);
+        }
+        self.pos += 1;
+        Ok(())
+    }
+
+    // Alphabetic abbreviation, or quoted one (<+01>) which may contain digits and signs
+    fn abbr(&mut self) -> Result<String, TzError> {
+        let start;
+        let end;
+        if self.peek() == Some(b'<') {
+            self.pos += 1;
+            start = self.pos;
+            while self
+                .peek()
+                .is_some_and(|c| c.is_ascii_alphanumeric() || c == b'+' || c == b'-')
+            {
+                self.pos += 1;
+            }
+            end = self.pos;
+            self.expect(b'>')?;
+        } else {
+            start = self.pos;
+            while self.peek().is_some_and(|c| c.is_ascii_alphabetic()) {
+                self.pos += 1;
+            }
+            end = self.pos;
+        }
+        if end - start < 3 {
+            return Err(TzError::ParseError);
+        }
+        // Only ASCII characters were accepted
+        Ok(String::from_utf8_lossy(&self.s[start..end]).to_string())
+    }
+
+    fn number(&mut self) -> Result<isize, TzError> {
+        let start = self.pos;
+        let mut n: isize = 0;
+        while let Some(c) = self.peek().filter(u8::is_ascii_digit) {
+            n = n
+                .checked_mul(10)
+                .and_then(|n| n.checked_add((c - b'0') as isize))
+                .ok_or(TzError::ParseError)?;
+            self.pos += 1;
+        }
+        if self.pos == start {
+            return Err(TzError::ParseError);
+        }
+        Ok(n)
+    }
+
+    // [+|-]hh[:mm[:ss]], in seconds
+    fn hms(&mut self) -> Result<isize, TzError> {
+        let sign = match self.peek() {
+            Some(b'-') => {
+                self.pos += 1;
+                -1
+            }
+            Some(b'+') => {
+                self.pos += 1;
+                1
+            }
+            _ => 1,
+        };
+        let hours = self.number()?;
+        if hours > 167 {
+            return Err(TzError::ParseError);
+        }
+        let mut seconds = hours * 3600;
+        for unit in [60, 1] {
+            if self.peek() != Some(b':') {
+                break;
+            }
+            self.pos += 1;
+            let n = self.number()?;
+            if n > 59 {
+                return Err(TzError::ParseError);
+            }
+            seconds += n * unit;
+        }
+        Ok(sign * seconds)
+    }
+
+    fn date(&mut self) -> Result<PosixDate, TzError> {
+        match self.peek() {
+            Some(b'J') => {
+                self.pos += 1;
+                match self.number()? {
+                    n @ 1..=365 => Ok(PosixDate::Julian(n as u16)),
+                    _ => Err(TzError::ParseError),
+                }
+            }
+            Some(b'M') => {
+                self.pos += 1;
+                let month = self.number()?;
+                self.expect(b'.')?;
+                let week = self.number()?;
+                self.expect(b'.')?;
+                let weekday = self.number()?;
+                if !(1..=12).contains(&month) || !(1..=5).contains(&week) || weekday > 6 {
+                    return Err(TzError::ParseError);
+                }
+                Ok(PosixDate::MonthWeekDay {
+                    month: month as u8,
+                    week: week as u8,
+                    weekday: weekday as u8,
+                })
+            }
+            _ => match self.number()? {
+                n @ 0..=365 => Ok(PosixDate::ZeroBased(n as u16)),
+                _ => Err(TzError::ParseError),
+            },
+        }
+    }
+
+    // Optional /time after a date, 02:00:00 by default
+    fn time(&mut self) -> Result<isize, TzError> {
+        if self.peek() == Some(b'/') {
+            self.pos += 1;
+            self.hms()
+        } else {
+            Ok(7200)
+        }
+    }
+}
@@ -0,0 +1,364 @@
+//! Python bindings, with PyO3 (available with the python feature, which includes the parse feature).
+//!
+//! ```text
+//! import datetime, libtzfile
+//! tz = libtzfile.Tz("/usr/share/zoneinfo/Europe/Paris")
+//! tz.transition_times(2020)
+//! tz.zoneinfo()
+//! tz.local_to_utc(datetime.datetime(2025, 3, 30, 2, 30), "compatible")
+//! datetime.datetime(2025, 7, 1, 12, tzinfo=tz.tzinfo())
+//! ```
+
+use crate::{
+    Disambiguation, LocalMapping, LocalTimeType, String, ToString, TransitionTime, Tz, TzError,
+    Tzinfo, Vec,
+};
+use chrono::{DateTime, Datelike, FixedOffset, NaiveDate, NaiveDateTime, TimeDelta, Timelike, Utc};
+use pyo3::{
+    create_exception,
+    exceptions::{PyTypeError, PyValueError},
+    prelude::*,
+    types::{PyDateAccess, PyDateTime, PyTimeAccess, PyTzInfo, PyTzInfoAccess},
+};
+use std::sync::Arc;
+
+create_exception!(
+    libtzfile,
+    TzfileError,
+    PyValueError,
+    "Error raised by libtzfile."
+);
+
+impl From<TzError> for PyErr {
+    fn from(e: TzError) -> PyErr {
+        TzfileError::new_err(e.to_string())
+    }
+}
+
+/// A zone, exported to Python as ```Tz```.
+#[pyclass(name = "Tz", module = "libtzfile", frozen, skip_from_py_object)]
+#[derive(Debug, Clone)]
+pub struct PyTz {
+    tz: Arc<Tz>,
+}
+
+/// A ```datetime.tzinfo``` subclass backed by a zone, exported to Python as ```ZoneInfo```.
+/// Ambiguous and skipped local times are resolved with the datetime's fold attribute, as specified by PEP 495.
+#[pyclass(
+    name = "ZoneInfo",
+    module = "libtzfile",
+    extends = PyTzInfo,
+    frozen,
+    skip_from_py_object
+)]
+#[derive(Debug, Clone)]
+pub struct PyZoneInfo {
+    tz: Arc<Tz>,
+    // DST amount of each local time type
+    dst_offsets: Vec<isize>,
+}
+
+#[pymethods]
+impl PyTz {
+    /// Reads and parses a TZfile (ie. /usr/share/zoneinfo/Europe/Paris).
+    #[new]
+    fn new(path: &str) -> PyResult<PyTz> {
+        Ok(PyTz {
+            tz: Arc::new(Tz::new(path)?),
+        })
+    }
+
+    /// Parses a TZfile from its content. The name is the zone's name (ie. Europe/Paris).
+    #[staticmethod]
+    fn from_bytes(buf: &[u8], name: &str) -> PyResult<PyTz> {
+        Ok(PyTz {
+            tz: Arc::new(Tz::from_bytes(buf, name)?),
+        })
+    }
+
+    /// Zone's name
+    #[getter]
+    fn name(&self) -> String {
+        self.tz.name.clone()
+    }
+
+    /// Returns year's transition times, as ```Tz::transition_times```: None for all years, 0 for the current year.
+    #[pyo3(signature = (year=None))]
+    fn transition_times(&self, year: Option<i32>) -> PyResult<Vec<TransitionTime>> {
+        Ok(self.tz.transition_times(year)?)
+    }
+
+    /// Returns the zone's informations at an aware datetime, or now.
+    #[pyo3(signature = (now=None))]
+    fn zoneinfo(&self, now: Option<&Bound<'_, PyDateTime>>) -> PyResult<Tzinfo> {
+        Ok(match now {
+            Some(now) => self.tz.zoneinfo_with(&utc(now)?)?,
+            None => self.tz.zoneinfo()?,
+        })
+    }
+
+    /// Returns the local time type (offset to UTC, DST and abbreviation) in effect at an aware datetime.
+    fn lookup(&self, instant: &Bound<'_, PyDateTime>) -> PyResult<LocalTimeType> {
+        Ok(self.tz.lookup(utc(instant)?))
+    }
+
+    /// Converts an aware datetime to the local time, with a fixed offset.
+    fn utc_to_local(&self, instant: &Bound<'_, PyDateTime>) -> PyResult<DateTime<FixedOffset>> {
+        let instant = utc(instant)?;
+        let offset = FixedOffset::east_opt(self.tz.lookup(instant).utc_offset as i32)
+            .ok_or(TzError::InvalidTimezone)?;
+        Ok(instant.with_timezone(&offset))
+    }
+
+    /// Converts a naive local time to UTC. Ambiguous and skipped local times are handled according to the
+    /// disambiguation policy: compatible, earlier, later or reject.
+    #[pyo3(signature = (local, disambiguation="compatible"))]
+    fn local_to_utc(&self, local: NaiveDateTime, disambiguation: &str) -> PyResult<DateTime<Utc>> {
+        let disambiguation = match disambiguation {
+            "compatible" => Disambiguation::Compatible,
+            "earlier" => Disambiguation::Earlier,
+            "later" => Disambiguation::Later,
+            "reject" => Disambiguation::Reject,
+            _ => {
+                return Err(PyValueError::new_err(format!(
+                    "unknown disambiguation: {}",
+                    disambiguation
+                )))
+            }
+        };
+        Ok(self.tz.local_to_utc(local, disambiguation)?)
+    }
+
+    /// Returns a ```datetime.tzinfo``` backed by the zone.
+    fn tzinfo(&self, py: Python<'_>) -> PyResult<Py<PyZoneInfo>> {
+        Py::new(py, PyZoneInfo::from(Arc::clone(&self.tz)))
+    }
+
+    fn __repr__(&self) -> String {
+        format!("libtzfile.Tz({:?})", self.tz.name)
+    }
+}
+
+#[pymethods]
+impl PyZoneInfo {
+    /// Reads and parses a TZfile (ie. /usr/share/zoneinfo/Europe/Paris).
+    #[new]
+    fn new(path: &str) -> PyResult<PyZoneInfo> {
+        Ok(PyZoneInfo::from(Arc::new(Tz::new(path)?)))
+    }
+
+    fn utcoffset(&self, dt: Option<&Bound<'_, PyDateTime>>) -> PyResult<Option<TimeDelta>> {
+        Ok(match dt {
+            Some(dt) => Some(TimeDelta::seconds(self.local_type(dt)?.utc_offset as i64)),
+            None => None,
+        })
+    }
+
+    fn dst(&self, dt: Option<&Bound<'_, PyDateTime>>) -> PyResult<Option<TimeDelta>> {
+        let dt = match dt {
+            Some(dt) => dt,
+            None => return Ok(None),
+        };
+        let (instant, ltt) = self.resolve(dt)?;
+        if !ltt.isdst {
+            return Ok(Some(TimeDelta::zero()));
+        }
+        let t = instant.timestamp();
+        let dst_offset = match (&self.tz.tz_footer, self.tz.tzh_timecnt_data.last()) {
+            (Some(footer), last) if last.is_none_or(|&last| t > last) => footer
+                .dst
+                .as_ref()
+                .map_or(0, |dst| dst.utoff - footer.std_utoff),
+            _ => {
+                let i = self.tz.tzh_timecnt_data.partition_point(|&x| x <= t);
+                let idx = i
+                    .checked_sub(1)
+                    .map_or(0, |i| self.tz.tzh_timecnt_indices[i] as usize);
+                self.dst_offsets[idx]
+            }
+        };
+        Ok(Some(TimeDelta::seconds(dst_offset as i64)))
+    }
+
+    fn tzname(&self, dt: Option<&Bound<'_, PyDateTime>>) -> PyResult<Option<String>> {
+        Ok(match dt {
+            Some(dt) => Some(self.local_type(dt)?.abbreviation),
+            None => None,
+        })
+    }
+
+    fn fromutc<'py>(
+        slf: &Bound<'py, Self>,
+        dt: &Bound<'py, PyDateTime>,
+    ) -> PyResult<Bound<'py, PyDateTime>> {
+        let tzinfo = match dt.get_tzinfo() {
+            Some(tzinfo) if tzinfo.is(slf) => tzinfo,
+            _ => return Err(PyValueError::new_err("fromutc: dt.tzinfo is not self")),
+        };
+        let zone = slf.get();
+        let instant = naive(dt)?.and_utc();
+        let ltt = zone.tz.lookup(instant);
+        let local = instant.naive_utc() + TimeDelta::seconds(ltt.utc_offset as i64);
+        // The later of two instants with the same local time has fold=1
+        let fold = matches!(
+            zone.tz.local_mapping(local)?,
+            LocalMapping::Ambiguous(_, later) if later.timestamp() == instant.timestamp()
+        );
+        PyDateTime::new_with_fold(
+            dt.py(),
+            local.year(),
+            local.month() as u8,
+            local.day() as u8,
+            local.hour() as u8,
+            local.minute() as u8,
+            local.second() as u8,
+            local.nanosecond() / 1000,
+            Some(&tzinfo),
+            fold,
+        )
+    }
+
+    fn __repr__(&self) -> String {
+        format!("libtzfile.ZoneInfo({:?})", self.tz.name)
+    }
+
+    fn __str__(&self) -> String {
+        self.tz.name.clone()
+    }
+}
+
+impl From<Arc<Tz>> for PyZoneInfo {
+    // The DST amount of a local time type is not recorded: it is inferred from the adjacent transitions exactly as the
+    // zoneinfo module does, for the same dst() results
+    fn from(tz: Arc<Tz>) -> PyZoneInfo {
+        let isdst: Vec<bool> = tz.tzh_typecnt.iter().map(|tti| tti.tt_isdst == 1).collect();
+        let utoff = |idx: usize| tz.tzh_typecnt[idx].tt_utoff;
+        let indices = &tz.tzh_timecnt_indices;
+        let mut dst_offsets = vec![0; isdst.len()];
+        let dst_count = isdst.iter().filter(|&&dst| dst).count();
+        let mut dst_found = 0;
+        let mut complete = false;
+        for i in 1..indices.len() {
+            if dst_found == dst_count {
+                complete = true;
+                break;
+            }
+            let idx = indices[i] as usize;
+            if !isdst[idx] || dst_offsets[idx] != 0 {
+                continue;
+            }
+            let previous = indices[i - 1] as usize;
+            let mut dst_offset = if isdst[previous] {
+                0
+            } else {
+                utoff(idx) - utoff(previous)
+            };
+            // As zoneinfo, which compares the local time type index
+            if dst_offset == 0 && idx + 1 < isdst.len() {
+                let next = match indices.get(i + 1) {
+                    Some(&next) => next as usize,
+                    None => continue,
+                };
+                if isdst[next] {
+                    continue;
+                }
+                dst_offset = utoff(idx) - utoff(next);
+            }
+            if dst_offset != 0 {
+                dst_found += 1;
+                dst_offsets[idx] = dst_offset;
+            }
+        }
+        // One hour is a better guess than none
+        if !complete {
+            for (dst_offset, &dst) in dst_offsets.iter_mut().zip(&isdst) {
+                if dst && *dst_offset == 0 {
+                    *dst_offset = 3600;
+                }
+            }
+        }
+        PyZoneInfo { tz, dst_offsets }
+    }
+}
+
+impl PyZoneInfo {
+    // Instant and local time type of a local datetime, fold=1 selecting the later instant of an ambiguous local time
+    // and the offset after the transition for a skipped one (PEP 495)
+    fn resolve(&self, dt: &Bound<'_, PyDateTime>) -> PyResult<(DateTime<Utc>, LocalTimeType)> {
+        let local = naive(dt)?;
+        let instant = match self.tz.local_mapping(local)? {
+            LocalMapping::Single(t) => t,
+            LocalMapping::Ambiguous(earlier, later) | LocalMapping::Gap(earlier, later) => {
+                if dt.get_fold() {
+                    later
+                } else {
+                    earlier
+                }
+            }
+        };
+        Ok((instant, self.tz.lookup(instant)))
+    }
+
+    fn local_type(&self, dt: &Bound<'_, PyDateTime>) -> PyResult<LocalTimeType> {
+        Ok(self.resolve(dt)?.1)
+    }
+}
+
+#[pymethods]
+impl TransitionTime {
+    fn __repr__(&self) -> String {
+        format!("{:?}", self)
+    }
+}
+
+#[pymethods]
+impl LocalTimeType {
+    fn __repr__(&self) -> String {
+        format!("{:?}", self)
+    }
+}
+
+#[pymethods]
+impl Tzinfo {
+    fn __repr__(&self) -> String {
+        format!("{:?}", self)
+    }
+}
+
+// Naive date and time of a datetime, its tzinfo being ignored
+fn naive(dt: &Bound<'_, PyDateTime>) -> PyResult<NaiveDateTime> {
+    NaiveDate::from_ymd_opt(dt.get_year(), dt.get_month().into(), dt.get_day().into())
+        .and_then(|d| {
+            d.and_hms_micro_opt(
+                dt.get_hour().into(),
+                dt.get_minute().into(),
+                dt.get_second().into(),
+                dt.get_microsecond(),
+            )
+        })
+        .ok_or_else(|| PyValueError::new_err("invalid datetime"))
+}
+
+// Instant of an aware datetime
+fn utc(dt: &Bound<'_, PyDateTime>) -> PyResult<DateTime<Utc>> {
+    if dt.get_tzinfo().is_none() {
+        return Err(PyTypeError::new_err(
+            "expected a datetime with non-None tzinfo",
+        ));
+    }
+    let utc = PyTzInfo::utc(dt.py())?;
+    let dt = dt.call_method1("astimezone", (utc,))?;
+    Ok(naive(dt.cast::<PyDateTime>()?)?.and_utc())
+}
+
+/// The libtzfile Python module.
+#[pymodule]
+pub(crate) fn libtzfile(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyTz>()?;
+    m.add_class::<PyZoneInfo>()?;
+    m.add_class::<TransitionTime>()?;
+    m.add_class::<LocalTimeType>()?;
+    m.add_class::<Tzinfo>()?;
+    m.add("TzfileError", m.py().get_type::<TzfileError>())?;
+    Ok(())
+}
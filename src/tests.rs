@@ -87,6 +87,17 @@ fn parse_abbr_amsterdam() {
     dbg!(Tz::new(timezone).unwrap());
 }
 
+#[cfg(target_family = "unix")]
+#[test]
+fn parse_abbr_suffix() {
+    // HST is stored at the end of AHST
+    let tz = Tz::new("/usr/share/zoneinfo/America/Adak").unwrap();
+    assert_eq!(tz.tz_abbr.last().map(String::as_str), Some("HST"));
+    let hst = tz.tzh_typecnt.last().unwrap();
+    assert_eq!((hst.tt_utoff, hst.tt_isdst), (-36000, 0));
+    assert_eq!(tz.tz_abbr[hst.tt_abbrind as usize], "HST");
+}
+
 #[cfg(any(feature = "parse", feature = "json"))]
 #[test]
 fn zonename() {
@@ -225,3 +236,1186 @@ fn emptytt() {
         Tz::new(timezone).unwrap().transition_times(None)
     );
 }
+
+// cargo test --features=json
+#[cfg(any(feature = "parse", feature = "json"))]
+#[test]
+fn zoneinfo_southern() {
+    #[cfg(target_family = "unix")]
+    let tz = Tz::new("/usr/share/zoneinfo/Australia/Sydney").unwrap();
+    #[cfg(target_os = "windows")]
+    let tz = Tz::new("c:\\Users\\nbauw\\Dev\\zoneinfo\\Australia\\Sydney").unwrap();
+    // DST period crossing the year boundary, ending this year
+    let summer = Utc.with_ymd_and_hms(2025, 1, 15, 12, 0, 0).unwrap();
+    // DST starting this year, ending the next one
+    let spring = Utc.with_ymd_and_hms(2025, 12, 15, 12, 0, 0).unwrap();
+    for (now, dst_from, dst_until) in [
+        (summer, (2024, 10, 5), (2025, 4, 5)),
+        (spring, (2025, 10, 4), (2026, 4, 4)),
+    ] {
+        let tztest = tz.zoneinfo_with(&now).unwrap();
+        assert!(tztest.dst_period);
+        assert_eq!(
+            (tztest.dst_from, tztest.dst_until),
+            (
+                Utc.with_ymd_and_hms(dst_from.0, dst_from.1, dst_from.2, 16, 0, 0)
+                    .single(),
+                Utc.with_ymd_and_hms(dst_until.0, dst_until.1, dst_until.2, 16, 0, 0)
+                    .single()
+            )
+        );
+        assert_eq!((tztest.raw_offset, tztest.dst_offset), (36000, 39600));
+        assert_eq!(tztest.abbreviation, "AEDT");
+    }
+    // Winter: the DST period starting later this year
+    let tztest = tz
+        .zoneinfo_with(&Utc.with_ymd_and_hms(2025, 7, 1, 12, 0, 0).unwrap())
+        .unwrap();
+    assert!(!tztest.dst_period);
+    assert_eq!(
+        (tztest.dst_from, tztest.dst_until),
+        (
+            Utc.with_ymd_and_hms(2025, 10, 4, 16, 0, 0).single(),
+            Utc.with_ymd_and_hms(2026, 4, 4, 16, 0, 0).single()
+        )
+    );
+    assert_eq!((tztest.raw_offset, tztest.dst_offset), (36000, 39600));
+    assert_eq!(tztest.abbreviation, "AEST");
+}
+
+#[cfg(any(feature = "parse", feature = "json"))]
+#[test]
+fn zoneinfo_ramadan() {
+    #[cfg(target_family = "unix")]
+    let tz = Tz::new("/usr/share/zoneinfo/Africa/Casablanca").unwrap();
+    #[cfg(target_os = "windows")]
+    let tz = Tz::new("c:\\Users\\nbauw\\Dev\\zoneinfo\\Africa\\Casablanca").unwrap();
+    let tztest = tz
+        .zoneinfo_with(&Utc.with_ymd_and_hms(2025, 3, 1, 12, 0, 0).unwrap())
+        .unwrap();
+    assert!(tztest.dst_period);
+    assert_eq!(
+        tztest.dst_from,
+        Utc.with_ymd_and_hms(2025, 2, 23, 2, 0, 0).single()
+    );
+    assert_eq!(
+        tztest.dst_until,
+        Utc.with_ymd_and_hms(2025, 4, 6, 2, 0, 0).single()
+    );
+    assert_eq!(tztest.raw_offset, 3600);
+    assert_eq!(tztest.dst_offset, 0);
+    assert_eq!(tztest.abbreviation, "+00");
+}
+
+#[cfg(any(feature = "parse", feature = "json"))]
+#[test]
+fn zoneinfo_footer() {
+    #[cfg(target_family = "unix")]
+    let mut tz = Tz::new("/usr/share/zoneinfo/Europe/Paris").unwrap();
+    #[cfg(target_os = "windows")]
+    let mut tz = Tz::new("c:\\Users\\nbauw\\Dev\\zoneinfo\\Europe\\Paris").unwrap();
+    // After the transition times recorded in fat TZfiles (up to 2037)
+    let now = Utc.with_ymd_and_hms(2040, 7, 1, 12, 0, 0).unwrap();
+    let tztest = tz.zoneinfo_with(&now).unwrap();
+    assert!(tztest.dst_period);
+    assert_eq!(tztest.abbreviation, "CEST");
+    assert_eq!(tztest.utc_offset.local_minus_utc(), 7200);
+    assert_eq!(
+        (tztest.dst_from, tztest.dst_until),
+        (
+            Utc.with_ymd_and_hms(2040, 3, 25, 1, 0, 0).single(),
+            Utc.with_ymd_and_hms(2040, 10, 28, 1, 0, 0).single()
+        )
+    );
+
+    // Slim TZfiles (zic -b slim) stop at 1996, the footer giving the following transitions
+    let slim = tz.tzh_timecnt_data.partition_point(|&t| t <= 846378000);
+    tz.tzh_timecnt_data.truncate(slim);
+    tz.tzh_timecnt_indices.truncate(slim);
+    let now = Utc.with_ymd_and_hms(2026, 1, 15, 12, 0, 0).unwrap();
+    let tztest = tz.zoneinfo_with(&now).unwrap();
+    assert!(!tztest.dst_period);
+    assert_eq!(tztest.abbreviation, "CET");
+    assert_eq!(tztest.datetime.to_rfc3339(), "2026-01-15T13:00:00+01:00");
+    assert_eq!(
+        (tztest.dst_from, tztest.dst_until),
+        (
+            Utc.with_ymd_and_hms(2026, 3, 29, 1, 0, 0).single(),
+            Utc.with_ymd_and_hms(2026, 10, 25, 1, 0, 0).single()
+        )
+    );
+    assert_eq!((tztest.raw_offset, tztest.dst_offset), (3600, 7200));
+    // The DST period which ended this year
+    let now = Utc.with_ymd_and_hms(2026, 12, 1, 12, 0, 0).unwrap();
+    let tztest = tz.zoneinfo_with(&now).unwrap();
+    assert!(!tztest.dst_period);
+    assert_eq!(
+        tztest.dst_until,
+        Utc.with_ymd_and_hms(2026, 10, 25, 1, 0, 0).single()
+    );
+}
+
+#[cfg(any(feature = "parse", feature = "json"))]
+#[test]
+fn zoneinfo_double_summer_time() {
+    #[cfg(target_family = "unix")]
+    let tz = Tz::new("/usr/share/zoneinfo/Europe/London").unwrap();
+    #[cfg(target_os = "windows")]
+    let tz = Tz::new("c:\\Users\\nbauw\\Dev\\zoneinfo\\Europe\\London").unwrap();
+    let tztest = tz
+        .zoneinfo_with(&Utc.with_ymd_and_hms(1941, 6, 1, 12, 0, 0).unwrap())
+        .unwrap();
+    assert!(tztest.dst_period);
+    assert_eq!(
+        tztest.dst_from,
+        Utc.with_ymd_and_hms(1940, 2, 25, 2, 0, 0).single()
+    );
+    assert_eq!(
+        tztest.dst_until,
+        Utc.with_ymd_and_hms(1945, 10, 7, 2, 0, 0).single()
+    );
+    assert_eq!(tztest.raw_offset, 0);
+    assert_eq!(tztest.dst_offset, 7200);
+    assert_eq!(tztest.abbreviation, "BDST");
+}
+
+#[test]
+fn parse_footer() {
+    #[cfg(target_os = "windows")]
+    let timezone = "c:\\Users\\nbauw\\Dev\\zoneinfo\\America\\Santiago";
+    #[cfg(target_family = "unix")]
+    let timezone = "/usr/share/zoneinfo/America/Santiago";
+    let footer = PosixTz {
+        raw: String::from("<-04>4<-03>,M9.1.6/24,M4.1.6/24"),
+        std_abbr: String::from("-04"),
+        std_utoff: -14400,
+        dst: Some(PosixDst {
+            abbr: String::from("-03"),
+            utoff: -10800,
+            start: PosixDate::MonthWeekDay {
+                month: 9,
+                week: 1,
+                weekday: 6,
+            },
+            start_time: 86400,
+            end: PosixDate::MonthWeekDay {
+                month: 4,
+                week: 1,
+                weekday: 6,
+            },
+            end_time: 86400,
+        }),
+    };
+    assert_eq!(Tz::new(timezone).unwrap().tz_footer, Some(footer));
+    assert_eq!(Tz::new(TIMEZONE).unwrap().tz_footer.unwrap().dst, None);
+}
+
+#[cfg(any(feature = "parse", feature = "json"))]
+#[test]
+fn transitions_between() {
+    #[cfg(target_family = "unix")]
+    let tz = Tz::new("/usr/share/zoneinfo/Europe/Paris").unwrap();
+    #[cfg(target_os = "windows")]
+    let tz = Tz::new("c:\\Users\\nbauw\\Dev\\zoneinfo\\Europe\\Paris").unwrap();
+    // Recorded transition times, start included and end excluded
+    let tt = tz.transitions_between(
+        Utc.with_ymd_and_hms(2019, 3, 31, 1, 0, 0).unwrap(),
+        Utc.with_ymd_and_hms(2019, 10, 27, 1, 0, 0).unwrap(),
+    );
+    assert_eq!(tt.len(), 1);
+    assert_eq!(tt[0].abbreviation, "CEST");
+    // Transition times generated from the footer
+    let tt = vec![
+        TransitionTime {
+            time: Utc.with_ymd_and_hms(2050, 3, 27, 1, 0, 0).unwrap(),
+            utc_offset: 7200,
+            isdst: true,
+            abbreviation: String::from("CEST"),
+        },
+        TransitionTime {
+            time: Utc.with_ymd_and_hms(2050, 10, 30, 1, 0, 0).unwrap(),
+            utc_offset: 3600,
+            isdst: false,
+            abbreviation: String::from("CET"),
+        },
+    ];
+    assert_eq!(
+        tz.transitions_between(
+            Utc.with_ymd_and_hms(2050, 1, 1, 0, 0, 0).unwrap(),
+            Utc.with_ymd_and_hms(2051, 1, 1, 0, 0, 0).unwrap()
+        ),
+        tt
+    );
+}
+
+#[cfg(any(feature = "parse", feature = "json"))]
+#[test]
+fn transitions_from() {
+    #[cfg(target_family = "unix")]
+    let tz = Tz::new("/usr/share/zoneinfo/Europe/Paris").unwrap();
+    #[cfg(target_os = "windows")]
+    let tz = Tz::new("c:\\Users\\nbauw\\Dev\\zoneinfo\\Europe\\Paris").unwrap();
+    let mut transitions = tz.transitions_from(Utc.with_ymd_and_hms(2037, 6, 1, 0, 0, 0).unwrap());
+    // Last recorded transition time, then generated from the footer
+    assert_eq!(
+        transitions.next(),
+        Some(Transition {
+            time: Utc.with_ymd_and_hms(2037, 10, 25, 1, 0, 0).unwrap(),
+            old_utc_offset: 7200,
+            new_utc_offset: 3600,
+            isdst: false,
+            abbreviation: String::from("CET"),
+        })
+    );
+    assert_eq!(
+        transitions.next(),
+        Some(Transition {
+            time: Utc.with_ymd_and_hms(2038, 3, 28, 1, 0, 0).unwrap(),
+            old_utc_offset: 3600,
+            new_utc_offset: 7200,
+            isdst: true,
+            abbreviation: String::from("CEST"),
+        })
+    );
+    let mut before = tz.transitions_before(Utc.with_ymd_and_hms(2037, 6, 1, 0, 0, 0).unwrap());
+    assert_eq!(
+        before.next().map(|t| t.time),
+        Utc.with_ymd_and_hms(2037, 3, 29, 1, 0, 0).single()
+    );
+
+    let tz = Tz::new(TIMEZONE).unwrap();
+    let now = Utc.with_ymd_and_hms(2020, 1, 1, 0, 0, 0).unwrap();
+    assert_eq!(tz.transitions_from(now).next(), None);
+    assert_eq!(tz.transitions_before(now).count(), 11);
+    // Walking backward from the end yields the forward transitions in reverse order
+    let start = Utc.with_ymd_and_hms(1800, 1, 1, 0, 0, 0).unwrap();
+    let mut forward: Vec<Transition> = tz.transitions_from(start).collect();
+    forward.reverse();
+    assert_eq!(tz.transitions_before(now).collect::<Vec<_>>(), forward);
+}
+
+#[cfg(any(feature = "parse", feature = "json"))]
+#[test]
+fn next_previous_transition() {
+    #[cfg(target_family = "unix")]
+    let tz = Tz::new("/usr/share/zoneinfo/America/Godthab").unwrap();
+    #[cfg(target_os = "windows")]
+    let tz = Tz::new("c:\\Users\\nbauw\\Dev\\zoneinfo\\America\\Godthab").unwrap();
+    // The 2038-01-19T03:14:07Z transition time does not change the local time type
+    assert_eq!(
+        tz.next_transition(Utc.with_ymd_and_hms(2037, 11, 1, 0, 0, 0).unwrap())
+            .map(|t| t.time),
+        Utc.with_ymd_and_hms(2038, 3, 28, 1, 0, 0).single()
+    );
+    assert_eq!(
+        tz.previous_transition(Utc.with_ymd_and_hms(2038, 3, 1, 0, 0, 0).unwrap())
+            .map(|t| t.time),
+        Utc.with_ymd_and_hms(2037, 10, 25, 1, 0, 0).single()
+    );
+    // Strictly after / before
+    let t = Utc.with_ymd_and_hms(2038, 3, 28, 1, 0, 0).unwrap();
+    assert!(tz.next_transition(t).unwrap().time > t);
+    assert!(tz.previous_transition(t).unwrap().time < t);
+
+    let tz = Tz {
+        tzh_version: 2,
+        tzh_timecnt_data: vec![BIG_BANG, 0],
+        tzh_timecnt_indices: vec![1, 0],
+        tzh_typecnt: vec![
+            Ttinfo {
+                tt_utoff: 0,
+                tt_isdst: 0,
+                tt_abbrind: 0,
+            },
+            Ttinfo {
+                tt_utoff: 3600,
+                tt_isdst: 0,
+                tt_abbrind: 1,
+            },
+        ],
+        tz_abbr: vec![String::from("UTC"), String::from("+01")],
+        tzh_leapcnt_data: vec![],
+        tz_footer: None,
+        name: String::from("Test"),
+    };
+    let epoch = Utc.timestamp_opt(0, 0).unwrap();
+    assert_eq!(tz.previous_transition(epoch), None);
+    assert_eq!(
+        tz.previous_transition(Utc.timestamp_opt(1, 0).unwrap())
+            .map(|t| t.time),
+        Some(epoch)
+    );
+    assert_eq!(tz.next_transition(epoch), None);
+}
+
+#[cfg(any(feature = "parse", feature = "json"))]
+#[test]
+fn clock() {
+    #[cfg(target_family = "unix")]
+    let tz = Tz::new("/usr/share/zoneinfo/Europe/Paris").unwrap();
+    #[cfg(target_os = "windows")]
+    let tz = Tz::new("c:\\Users\\nbauw\\Dev\\zoneinfo\\Europe\\Paris").unwrap();
+    // New Year's Eve in Paris, but still the previous year in UTC
+    let now = Utc.with_ymd_and_hms(2019, 12, 31, 23, 30, 0).unwrap();
+    assert_eq!(
+        tz.transition_times_with(YearSelection::Current, &now)
+            .unwrap(),
+        tz.transition_times(2019).unwrap()
+    );
+    assert_eq!(YearSelection::from(Some(0)), YearSelection::Current);
+    let tzinfo = tz.zoneinfo_with(&now).unwrap();
+    assert_eq!(tzinfo.utc_datetime, now);
+    assert_eq!(tzinfo.week_number, 1);
+    assert!(!tzinfo.dst_period);
+}
+
+#[cfg(any(feature = "parse", feature = "json"))]
+#[test]
+fn format() {
+    #[cfg(target_family = "unix")]
+    let tz = Tz::new("/usr/share/zoneinfo/Europe/Paris").unwrap();
+    #[cfg(target_os = "windows")]
+    let tz = Tz::new("c:\\Users\\nbauw\\Dev\\zoneinfo\\Europe\\Paris").unwrap();
+    let instant = Utc.with_ymd_and_hms(1890, 6, 1, 12, 0, 0).unwrap();
+    assert_eq!(
+        tz.format(instant, "%Y-%m-%d %H:%M:%S %Z %z %:z %::z %:::z %s %%Z")
+            .unwrap(),
+        "1890-06-01 12:09:21 LMT +000921 +00:09:21 +00:09:21 +00:09:21 -2511432000 %Z"
+    );
+    let instant = Utc.with_ymd_and_hms(2020, 7, 14, 10, 0, 0).unwrap();
+    assert_eq!(
+        tz.format(instant, "%A %e %B %Y, %-I:%M %p %Z (%z, %:z, %::z, %:::z)")
+            .unwrap(),
+        "Tuesday 14 July 2020, 12:00 PM CEST (+0200, +02:00, +02:00:00, +02)"
+    );
+    assert_eq!(tz.format(instant, "%Q"), Err(TzError::InvalidFormat));
+}
+
+#[cfg(any(feature = "parse", feature = "json"))]
+#[test]
+fn local_to_utc() {
+    use chrono::NaiveDate;
+    #[cfg(target_family = "unix")]
+    let tz = Tz::new("/usr/share/zoneinfo/Europe/Paris").unwrap();
+    #[cfg(target_os = "windows")]
+    let tz = Tz::new("c:\\Users\\nbauw\\Dev\\zoneinfo\\Europe\\Paris").unwrap();
+    let utc = |h, m| Utc.with_ymd_and_hms(2025, 3, 30, h, m, 0).unwrap();
+    // Skipped when DST starts
+    let local = NaiveDate::from_ymd_opt(2025, 3, 30)
+        .unwrap()
+        .and_hms_opt(2, 30, 0)
+        .unwrap();
+    assert_eq!(
+        tz.local_mapping(local).unwrap(),
+        LocalMapping::Gap(utc(0, 30), utc(1, 30))
+    );
+    assert_eq!(
+        tz.local_to_utc(local, Disambiguation::Compatible).unwrap(),
+        utc(1, 30)
+    );
+    assert_eq!(
+        tz.local_to_utc(local, Disambiguation::Reject),
+        Err(TzError::SkippedLocalTime)
+    );
+    // Occurring twice when DST ends
+    let utc = |h, m| Utc.with_ymd_and_hms(2025, 10, 26, h, m, 0).unwrap();
+    let local = NaiveDate::from_ymd_opt(2025, 10, 26)
+        .unwrap()
+        .and_hms_opt(2, 30, 0)
+        .unwrap();
+    assert_eq!(
+        tz.local_mapping(local).unwrap(),
+        LocalMapping::Ambiguous(utc(0, 30), utc(1, 30))
+    );
+    assert_eq!(
+        tz.local_to_utc(local, Disambiguation::Later).unwrap(),
+        utc(1, 30)
+    );
+    // In the footer's range
+    let local = NaiveDate::from_ymd_opt(2050, 7, 1)
+        .unwrap()
+        .and_hms_opt(12, 0, 0)
+        .unwrap();
+    assert_eq!(
+        tz.local_mapping(local).unwrap(),
+        LocalMapping::Single(Utc.with_ymd_and_hms(2050, 7, 1, 10, 0, 0).unwrap())
+    );
+    // Fractional seconds are kept
+    let local = NaiveDate::from_ymd_opt(2025, 1, 1)
+        .unwrap()
+        .and_hms_milli_opt(12, 0, 0, 500)
+        .unwrap();
+    assert_eq!(
+        tz.local_to_utc(local, Disambiguation::Reject)
+            .unwrap()
+            .to_rfc3339(),
+        "2025-01-01T11:00:00.500+00:00"
+    );
+    let local = NaiveDate::from_ymd_opt(2025, 3, 30)
+        .unwrap()
+        .and_hms_milli_opt(2, 59, 59, 999)
+        .unwrap();
+    assert_eq!(
+        tz.local_to_utc(local, Disambiguation::Compatible)
+            .unwrap()
+            .to_rfc3339(),
+        "2025-03-30T01:59:59.999+00:00"
+    );
+}
+
+#[cfg(all(target_family = "unix", any(feature = "parse", feature = "json")))]
+#[test]
+fn parse_datetime() {
+    let loader = ZoneLoader::default();
+    let parsed = loader
+        .parse_datetime(
+            "2024-03-10 02:30:00 America/New_York",
+            Disambiguation::Compatible,
+            &[],
+        )
+        .unwrap();
+    assert_eq!(
+        parsed.utc,
+        Utc.with_ymd_and_hms(2024, 3, 10, 7, 30, 0).unwrap()
+    );
+    assert_eq!(parsed.abbreviation, "EDT");
+    assert_eq!(
+        loader.parse_datetime(
+            "2024-03-10 02:30:00 America/New_York",
+            Disambiguation::Reject,
+            &[]
+        ),
+        Err(TzError::SkippedLocalTime)
+    );
+    let parsed = loader
+        .parse_datetime(
+            "Sun Mar 10 02:30:00 EST 2024",
+            Disambiguation::Reject,
+            &["America/New_York"],
+        )
+        .unwrap();
+    assert_eq!(
+        parsed.utc,
+        Utc.with_ymd_and_hms(2024, 3, 10, 7, 30, 0).unwrap()
+    );
+    assert_eq!(parsed.zone.as_deref(), Some("America/New_York"));
+    assert_eq!(
+        loader.parse_datetime("Sun Mar 10 02:30:00 IST 2024", Disambiguation::Reject, &[]),
+        Err(TzError::AmbiguousAbbreviation)
+    );
+    let parsed = loader
+        .parse_datetime(
+            "Sun Mar 10 02:30:00 IST 2024",
+            Disambiguation::Reject,
+            &["Asia/Kolkata"],
+        )
+        .unwrap();
+    assert_eq!(parsed.local.offset().local_minus_utc(), 19800);
+    let parsed = loader
+        .parse_datetime(
+            "2024-03-10 01:59:59.250 America/New_York",
+            Disambiguation::Reject,
+            &[],
+        )
+        .unwrap();
+    assert_eq!(parsed.utc.to_rfc3339(), "2024-03-10T06:59:59.250+00:00");
+}
+
+#[cfg(all(target_family = "unix", any(feature = "parse", feature = "json")))]
+#[test]
+fn corrupt_tzfile() {
+    let buf = std::fs::read("/usr/share/zoneinfo/Europe/Paris").unwrap();
+    assert_eq!(
+        Tz::from_bytes(b"not a TZfile", "Europe/Paris"),
+        Err(TzError::InvalidMagic)
+    );
+    for len in [10, 100, buf.len() / 2] {
+        assert_eq!(
+            Tz::from_bytes(&buf[..len], "Europe/Paris"),
+            Err(TzError::ParseError)
+        );
+    }
+    // Local time type index out of range
+    let mut corrupt = buf.clone();
+    let header = Tz::parse_header(&buf).unwrap();
+    corrupt[0x2C + header.v2_header_start + header.tzh_timecnt * 8] = 0xff;
+    assert_eq!(
+        Tz::from_bytes(&corrupt, "Europe/Paris"),
+        Err(TzError::ParseError)
+    );
+    // Offsets to UTC beyond one day are valid in TZfiles, but not in chrono
+    let mut tz = Tz::from_bytes(&buf, "Europe/Paris").unwrap();
+    for ttinfo in &mut tz.tzh_typecnt {
+        ttinfo.tt_utoff = 90000;
+    }
+    let now = Utc.with_ymd_and_hms(2020, 1, 15, 12, 0, 0).unwrap();
+    assert_eq!(tz.zoneinfo_with(&now).err(), Some(TzError::InvalidTimezone));
+}
+
+#[cfg(all(target_family = "unix", any(feature = "parse", feature = "json")))]
+#[test]
+fn ixdtf() {
+    let loader = ZoneLoader::default();
+    let parse = |s| loader.parse_ixdtf(s, Disambiguation::Reject);
+    let parsed = parse("2025-06-01T12:00:00+02:00[Europe/Paris]").unwrap();
+    assert_eq!(
+        parsed.utc,
+        Utc.with_ymd_and_hms(2025, 6, 1, 10, 0, 0).unwrap()
+    );
+    assert_eq!(parsed.zone.as_deref(), Some("Europe/Paris"));
+    assert_eq!(parsed.abbreviation, "CEST");
+    // Offset inconsistent with the zone
+    assert_eq!(
+        parse("2025-06-01T12:00:00+01:00[!Europe/Paris]"),
+        Err(TzError::InconsistentOffset)
+    );
+    let parsed = parse("2025-06-01T12:00:00+01:00[Europe/Paris]").unwrap();
+    assert_eq!(
+        parsed.utc,
+        Utc.with_ymd_and_hms(2025, 6, 1, 11, 0, 0).unwrap()
+    );
+    assert_eq!((parsed.zone, parsed.abbreviation.as_str()), (None, "+01"));
+    // Offset choosing between the instants of an ambiguous local time
+    let parsed = parse("2025-10-26T02:30:00+01:00[!Europe/Paris]").unwrap();
+    assert_eq!(
+        parsed.utc,
+        Utc.with_ymd_and_hms(2025, 10, 26, 1, 30, 0).unwrap()
+    );
+    assert_eq!(
+        parse("2025-10-26T02:30:00[Europe/Paris]"),
+        Err(TzError::AmbiguousLocalTime)
+    );
+    let parsed = parse("2025-06-01T12:00:00.125[Europe/Paris]").unwrap();
+    assert_eq!(parsed.utc.to_rfc3339(), "2025-06-01T10:00:00.125+00:00");
+    // Unknown local offset
+    let parsed = parse("2025-06-01T10:00:00Z[Europe/Paris]").unwrap();
+    assert_eq!(parsed.local.to_rfc3339(), "2025-06-01T12:00:00+02:00");
+    // Annotations
+    assert!(parse("2025-06-01T12:00:00+02:00[Europe/Paris][u-ca=iso8601]").is_ok());
+    assert!(parse("2025-06-01T12:00:00+02:00[Europe/Paris][foo=bar]").is_ok());
+    assert_eq!(
+        parse("2025-06-01T12:00:00+02:00[Europe/Paris][!foo=bar]"),
+        Err(TzError::UnsupportedAnnotation)
+    );
+    assert_eq!(
+        parse("2025-06-01T12:00:00+02:00[Europe/Paris"),
+        Err(TzError::ParseError)
+    );
+    // LMT offset rounded to the minute
+    let tz = loader.load("Europe/Paris").unwrap();
+    let instant = Utc.with_ymd_and_hms(1890, 6, 1, 12, 0, 0).unwrap();
+    let formatted = tz.format_ixdtf(instant, true).unwrap();
+    assert_eq!(formatted, "1890-06-01T12:09:21+00:09[!Europe/Paris]");
+    assert_eq!(parse(&formatted).unwrap().utc, instant);
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn serde_round_trip() {
+    #[cfg(target_family = "unix")]
+    let tz = Tz::new("/usr/share/zoneinfo/America/Santiago").unwrap();
+    #[cfg(target_os = "windows")]
+    let tz = Tz::new("c:\\Users\\nbauw\\Dev\\zoneinfo\\America\\Santiago").unwrap();
+    let json = serde_json::to_string(&tz).unwrap();
+    let cached: Tz = serde_json::from_str(&json).unwrap();
+    assert_eq!(cached, tz);
+    assert_eq!(cached.zoneinfo().unwrap().timezone, "America/Santiago");
+    // Tables the lookups could not rely on are rejected
+    let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    let malformed = |field: &str, table: serde_json::Value| {
+        let mut value = value.clone();
+        value[field] = table;
+        serde_json::from_value::<Tz>(value)
+    };
+    assert!(malformed("tzh_typecnt", serde_json::json!([])).is_err());
+    assert!(malformed("tzh_timecnt_indices", serde_json::json!([200])).is_err());
+    assert!(malformed("tz_abbr", serde_json::json!([])).is_err());
+    assert!(malformed("tzh_timecnt_data", serde_json::json!([0, -1])).is_err());
+    // Footer not matching its TZ string
+    let mut footer = value["tz_footer"].clone();
+    footer["dst"]["start"] =
+        serde_json::json!({"MonthWeekDay": {"month": 13, "week": 0, "weekday": 6}});
+    assert!(serde_json::from_value::<PosixTz>(footer.clone()).is_err());
+    assert!(malformed("tz_footer", footer).is_err());
+    assert_eq!(
+        serde_json::from_value::<PosixTz>(value["tz_footer"].clone()).ok(),
+        tz.tz_footer
+    );
+
+    let transitions = tz.transition_times(Some(2025)).unwrap();
+    let json = serde_json::to_string(&transitions).unwrap();
+    assert_eq!(
+        serde_json::from_str::<Vec<TransitionTime>>(&json).unwrap(),
+        transitions
+    );
+
+    // Offsets with seconds (LMT)
+    #[cfg(target_family = "unix")]
+    let tz = Tz::new("/usr/share/zoneinfo/Europe/Paris").unwrap();
+    #[cfg(target_os = "windows")]
+    let tz = Tz::new("c:\\Users\\nbauw\\Dev\\zoneinfo\\Europe\\Paris").unwrap();
+    let clock = Utc.with_ymd_and_hms(1890, 6, 1, 12, 0, 0).unwrap();
+    let json = tz.zoneinfo_with(&clock).unwrap().to_json().unwrap();
+    let tzinfo: Tzinfo = serde_json::from_str(&json).unwrap();
+    assert_eq!(tzinfo.utc_offset.local_minus_utc(), 561);
+    assert_eq!(tzinfo.to_json().unwrap(), json);
+    assert!(serde_json::from_str::<Tzinfo>(&json.replace("+00:09:21", "+09")).is_err());
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn history() {
+    #[cfg(target_family = "unix")]
+    let tz = Tz::new("/usr/share/zoneinfo/America/Phoenix").unwrap();
+    #[cfg(target_os = "windows")]
+    let tz = Tz::new("c:\\Users\\nbauw\\Dev\\zoneinfo\\America\\Phoenix").unwrap();
+    let history = tz.history();
+    assert_eq!(history.timezone, "America/Phoenix");
+    assert_eq!(history.transitions.len(), tz.tzh_timecnt_data.len());
+    assert_eq!(
+        history.transitions[0],
+        Transition {
+            time: Utc.timestamp_opt(-2717643600, 0).unwrap(),
+            old_utc_offset: -26898,
+            new_utc_offset: -25200,
+            isdst: false,
+            abbreviation: String::from("MST"),
+        }
+    );
+    assert_eq!(history.footer.as_ref().unwrap().raw, "MST7");
+    assert!(history.leap_seconds.is_empty());
+    let json = history.to_json().unwrap();
+    assert_eq!(serde_json::from_str::<ZoneHistory>(&json).unwrap(), history);
+}
+
+#[cfg(all(target_os = "linux", feature = "json"))]
+#[test]
+fn leap_seconds() {
+    let tz = Tz::new("/usr/share/zoneinfo/right/UTC").unwrap();
+    assert_eq!(
+        tz.tzh_leapcnt_data[0],
+        LeapSecond {
+            ls_trans: 78796800,
+            ls_corr: 1
+        }
+    );
+    assert_eq!(tz.tzh_leapcnt_data.last().unwrap().ls_corr, 27);
+    assert_eq!(tz.history().leap_seconds.len(), 27);
+}
+
+#[cfg(any(feature = "parse", feature = "json"))]
+#[test]
+fn vtimezone() {
+    #[cfg(target_family = "unix")]
+    let tz = Tz::new("/usr/share/zoneinfo/Asia/Jerusalem").unwrap();
+    #[cfg(target_os = "windows")]
+    let tz = Tz::new("c:\\Users\\nbauw\\Dev\\zoneinfo\\Asia\\Jerusalem").unwrap();
+    let ics = tz.vtimezone(2020).unwrap();
+    let lines: Vec<&str> = ics.split_terminator("\r\n").collect();
+    assert_eq!(lines[..2], ["BEGIN:VTIMEZONE", "TZID:Asia/Jerusalem"]);
+    // The Friday before the last Sunday of March (M3.4.4/26)
+    assert_eq!(
+        lines[8..15],
+        [
+            "BEGIN:DAYLIGHT",
+            "TZOFFSETFROM:+0200",
+            "TZOFFSETTO:+0300",
+            "TZNAME:IDT",
+            "DTSTART:20200327T020000",
+            "RRULE:FREQ=YEARLY;BYMONTH=3;BYDAY=FR;BYMONTHDAY=23,24,25,26,27,28,29",
+            "END:DAYLIGHT",
+        ]
+    );
+    assert_eq!(lines.last(), Some(&"END:VTIMEZONE"));
+
+    // Irregular history
+    #[cfg(target_family = "unix")]
+    let tz = Tz::new("/usr/share/zoneinfo/America/New_York").unwrap();
+    #[cfg(target_os = "windows")]
+    let tz = Tz::new("c:\\Users\\nbauw\\Dev\\zoneinfo\\America\\New_York").unwrap();
+    let ics = tz.vtimezone(1974).unwrap();
+    assert!(ics
+        .contains("DTSTART:19740106T020000\r\nRDATE:19750223T020000\r\nRDATE:19760425T020000\r\n"));
+    assert!(ics.contains("DTSTART:20070311T020000\r\nRRULE:FREQ=YEARLY;BYMONTH=3;BYDAY=2SU\r\n"));
+    assert!(ics.contains("DTSTART:20071104T020000\r\nRRULE:FREQ=YEARLY;BYMONTH=11;BYDAY=1SU\r\n"));
+}
+
+#[cfg(all(target_family = "unix", any(feature = "parse", feature = "json")))]
+#[test]
+fn vtimezone_import() {
+    let ics = "BEGIN:VTIMEZONE\r\nTZID:W. Europe Standard Time\r\n\
+        BEGIN:STANDARD\r\nDTSTART:16010101T030000\r\nTZOFFSETFROM:+0200\r\nTZOFFSETTO:+0100\r\n\
+        RRULE:FREQ=YEARLY;BYDAY=-1SU;BYMONTH=10\r\nEND:STANDARD\r\n\
+        BEGIN:DAYLIGHT\r\nDTSTART:16010101T020000\r\nTZOFFSETFROM:+0100\r\nTZOFFSETTO:+0200\r\n\
+        RRULE:FREQ=YEARLY;BYDAY=-1SU;BYMONTH=3\r\nEND:DAYLIGHT\r\nEND:VTIMEZONE\r\n";
+    let tz = Tz::from_vtimezone(ics).unwrap();
+    assert_eq!(
+        tz.tz_footer.as_ref().unwrap().raw,
+        "<+01>-1<+02>,M3.5.0,M10.5.0/3"
+    );
+    assert_eq!(
+        tz.lookup(Utc.with_ymd_and_hms(2025, 7, 1, 0, 0, 0).unwrap()),
+        LocalTimeType {
+            utc_offset: 7200,
+            isdst: true,
+            abbreviation: String::from("+02")
+        }
+    );
+    assert_eq!(
+        Tz::from_vtimezone("BEGIN:VTIMEZONE\r\nEND:VTIMEZONE\r\n"),
+        Err(TzError::ParseError)
+    );
+    // Huge INTERVAL
+    assert_eq!(
+        Tz::from_vtimezone(&ics.replace("FREQ=YEARLY;", "FREQ=YEARLY;INTERVAL=2147483647;")),
+        Err(TzError::ParseError)
+    );
+
+    // Export then import: same local times, and the closest zone is the original one
+    let loader = ZoneLoader::default();
+    let from = Utc.with_ymd_and_hms(1971, 1, 1, 0, 0, 0).unwrap();
+    let until = Utc.with_ymd_and_hms(2100, 1, 1, 0, 0, 0).unwrap();
+    for name in ["Asia/Jerusalem", "America/Adak"] {
+        let tz = loader.load(name).unwrap();
+        let imported = Tz::from_vtimezone(&tz.vtimezone(1970).unwrap()).unwrap();
+        assert_eq!(
+            imported.transitions_between(from, until),
+            tz.transitions_between(from, until)
+        );
+        assert_eq!(
+            loader
+                .closest_zone(&imported, from, until)
+                .unwrap()
+                .as_deref(),
+            Some(name)
+        );
+    }
+}
+
+#[cfg(any(feature = "parse", feature = "json"))]
+#[test]
+fn zic_source() {
+    #[cfg(target_family = "unix")]
+    let tz = Tz::new("/usr/share/zoneinfo/Europe/Paris").unwrap();
+    #[cfg(target_os = "windows")]
+    let tz = Tz::new("c:\\Users\\nbauw\\Dev\\zoneinfo\\Europe\\Paris").unwrap();
+    let zic = tz.zic_source().unwrap();
+    let lines: Vec<&str> = zic.lines().collect();
+    assert_eq!(
+        lines[lines.len() - 7..],
+        [
+            "# Zone\tNAME\tSTDOFF\tRULES\tFORMAT\t[UNTIL]",
+            "Zone\tEurope/Paris\t0:09:21\t-\tLMT\t1891 Mar 16",
+            "\t\t\t0:09:21\t-\tPMT\t1911 Mar 11",
+            "\t\t\t0:00\tParis\tWE%sT\t1940 Jun 14 23:00",
+            "\t\t\t1:00\tParis\tCE%sT\t1944 Aug 25",
+            "\t\t\t1:00\tParis\tWE%sT\t1945 Sep 16 3:00",
+            "\t\t\t1:00\tParis\tCE%sT",
+        ]
+    );
+    // The recorded transitions since 1981 follow the footer rule
+    assert!(lines.contains(&"Rule\tParis\t1981\tmax\t-\tMar\tlastSun\t2:00\t1:00\tS"));
+    assert!(lines.contains(&"Rule\tParis\t1996\tmax\t-\tOct\tlastSun\t3:00\t0\t-"));
+
+    // The Friday before the last Sunday of March (M3.4.4/26)
+    #[cfg(target_family = "unix")]
+    let tz = Tz::new("/usr/share/zoneinfo/Asia/Jerusalem").unwrap();
+    #[cfg(target_os = "windows")]
+    let tz = Tz::new("c:\\Users\\nbauw\\Dev\\zoneinfo\\Asia\\Jerusalem").unwrap();
+    let zic = tz.zic_source().unwrap();
+    assert!(zic.contains("Rule\tJerusalem\t2013\tmax\t-\tMar\tThu>=22\t26:00\t1:00\tD\n"));
+    assert!(zic.ends_with("\t\t\t2:00\tJerusalem\tI%sT\n"));
+}
+
+#[cfg(any(feature = "parse", feature = "json"))]
+#[test]
+fn eras() {
+    #[cfg(target_family = "unix")]
+    let tz = Tz::new("/usr/share/zoneinfo/Europe/Paris").unwrap();
+    #[cfg(target_os = "windows")]
+    let tz = Tz::new("c:\\Users\\nbauw\\Dev\\zoneinfo\\Europe\\Paris").unwrap();
+    let eras = tz.eras().unwrap();
+    let formats: Vec<&str> = eras.iter().map(|e| e.format.as_str()).collect();
+    assert_eq!(formats, ["LMT", "PMT", "WE%sT", "CE%sT", "WE%sT", "CE%sT"]);
+    assert_eq!(
+        eras[0],
+        Era {
+            start: None,
+            end: Some(Utc.with_ymd_and_hms(1891, 3, 15, 23, 50, 39).unwrap()),
+            std_offset: 561,
+            dst_rules: false,
+            save: 0,
+            format: String::from("LMT")
+        }
+    );
+    assert_eq!(
+        eras[5],
+        Era {
+            start: Some(Utc.with_ymd_and_hms(1945, 9, 16, 1, 0, 0).unwrap()),
+            end: None,
+            std_offset: 3600,
+            dst_rules: true,
+            save: 3600,
+            format: String::from("CE%sT")
+        }
+    );
+    // Contiguous
+    assert!(eras.windows(2).all(|w| w[0].end == w[1].start));
+
+    // DST rules are no longer in force after the last DST period
+    let eras = Tz::new(TIMEZONE).unwrap().eras().unwrap();
+    assert_eq!(
+        eras.last(),
+        Some(&Era {
+            start: Some(Utc.with_ymd_and_hms(1967, 10, 29, 8, 0, 0).unwrap()),
+            end: None,
+            std_offset: -25200,
+            dst_rules: false,
+            save: 0,
+            format: String::from("MST")
+        })
+    );
+}
+
+#[cfg(all(target_family = "unix", any(feature = "parse", feature = "json")))]
+#[test]
+fn windows_zones() {
+    let windows_zones = WindowsZones::bundled();
+    assert_eq!(
+        windows_zones.to_iana("W. Europe Standard Time", None),
+        Some("Europe/Berlin")
+    );
+    assert_eq!(
+        windows_zones.to_iana("w. europe standard time", Some("ch")),
+        Some("Europe/Zurich")
+    );
+    // No mapping for the territory: default zone
+    assert_eq!(
+        windows_zones.to_iana("W. Europe Standard Time", Some("US")),
+        Some("Europe/Berlin")
+    );
+    assert_eq!(windows_zones.to_iana("Mars Standard Time", None), None);
+    assert_eq!(
+        windows_zones.to_windows("America/Indiana/Knox"),
+        Some("Central Standard Time")
+    );
+
+    let xml = "<supplementalData><windowsZones><mapTimezones>\
+        <mapZone other=\"Pacific Standard Time\" territory=\"CA\" type=\"America/Vancouver America/Dawson\"/>\
+        </mapTimezones></windowsZones></supplementalData>";
+    let windows_zones = WindowsZones::from_xml(xml).unwrap();
+    assert_eq!(
+        windows_zones.mappings(),
+        [MapZone {
+            windows_id: String::from("Pacific Standard Time"),
+            territory: String::from("CA"),
+            zones: vec![
+                String::from("America/Vancouver"),
+                String::from("America/Dawson")
+            ],
+        }]
+    );
+    assert_eq!(
+        WindowsZones::from_xml("<mapTimezones><mapZone other=\"UTC\"/>"),
+        Err(TzError::ParseError)
+    );
+
+    let loader = ZoneLoader::default();
+    let windows_zones = WindowsZones::bundled();
+    let tz = loader
+        .load_windows(windows_zones, "Tokyo Standard Time", None)
+        .unwrap();
+    assert_eq!(tz.zoneinfo().unwrap().timezone, "Asia/Tokyo");
+    // Mapped as Asia/Calcutta
+    assert_eq!(
+        loader.windows_id(windows_zones, "Asia/Kolkata").unwrap(),
+        Some("India Standard Time")
+    );
+}
+
+#[cfg(any(feature = "parse", feature = "json"))]
+#[test]
+fn display_name() {
+    let metazones = MetaZones::from_xml(
+        r#"<supplementalData><metaZones><metazoneInfo>
+        <timezone type="Europe/Lisbon">
+            <usesMetazone to="1976-09-26 00:00" mzone="Europe_Central"/>
+            <usesMetazone to="1992-09-27 01:00" from="1976-09-26 00:00" mzone="Europe_Western"/>
+            <usesMetazone to="1996-03-31 01:00" from="1992-09-27 01:00" mzone="Europe_Central"/>
+            <usesMetazone from="1996-03-31 01:00" mzone="Europe_Western"/>
+        </timezone>
+        <timezone type="Europe/London">
+            <usesMetazone to="1971-10-31 02:00" mzone="British"/>
+            <usesMetazone from="1971-10-31 02:00" mzone="GMT"/>
+        </timezone>
+        </metazoneInfo></metaZones></supplementalData>"#,
+    )
+    .unwrap();
+    let fr = TimeZoneNames::from_xml(
+        r#"<ldml><dates><timeZoneNames>
+        <metazone type="Europe_Central"><long>
+            <generic>heure d’Europe centrale</generic>
+            <standard>heure normale d’Europe centrale</standard>
+            <daylight>heure d’été d’Europe centrale</daylight>
+        </long></metazone>
+        <metazone type="Europe_Western"><long>
+            <generic>heure d’Europe de l’Ouest</generic>
+            <standard>heure normale d’Europe de l’Ouest</standard>
+            <daylight>heure d’été d’Europe de l’Ouest</daylight>
+        </long></metazone>
+        </timeZoneNames></dates></ldml>"#,
+    )
+    .unwrap();
+    let en = TimeZoneNames::from_xml(
+        r#"<ldml><dates><timeZoneNames>
+        <zone type="Europe/London"><long><daylight>British Summer Time</daylight></long></zone>
+        <metazone type="GMT"><long><standard>Greenwich Mean Time</standard></long>
+            <short><standard>GMT</standard></short></metazone>
+        </timeZoneNames></dates></ldml>"#,
+    )
+    .unwrap();
+
+    #[cfg(target_family = "unix")]
+    let tz = Tz::new("/usr/share/zoneinfo/Europe/Lisbon").unwrap();
+    #[cfg(target_os = "windows")]
+    let tz = Tz::new("c:\\Users\\nbauw\\Dev\\zoneinfo\\Europe\\Lisbon").unwrap();
+    // Central European Time from 1992 to 1996
+    let instant = Utc.with_ymd_and_hms(1994, 7, 1, 0, 0, 0).unwrap();
+    assert_eq!(
+        tz.display_name(&fr, &metazones, instant, NameLength::Long)
+            .as_deref(),
+        Some("heure d’été d’Europe centrale")
+    );
+    let instant = Utc.with_ymd_and_hms(2025, 1, 15, 0, 0, 0).unwrap();
+    assert_eq!(
+        tz.display_name(&fr, &metazones, instant, NameLength::Long)
+            .as_deref(),
+        Some("heure normale d’Europe de l’Ouest")
+    );
+    assert_eq!(
+        tz.display_name(&fr, &metazones, instant, NameLength::Short),
+        None
+    );
+
+    #[cfg(target_family = "unix")]
+    let tz = Tz::new("/usr/share/zoneinfo/Europe/London").unwrap();
+    #[cfg(target_os = "windows")]
+    let tz = Tz::new("c:\\Users\\nbauw\\Dev\\zoneinfo\\Europe\\London").unwrap();
+    let summer = Utc.with_ymd_and_hms(2025, 7, 1, 0, 0, 0).unwrap();
+    let winter = Utc.with_ymd_and_hms(2025, 1, 15, 0, 0, 0).unwrap();
+    assert_eq!(
+        tz.display_name(&en, &metazones, summer, NameLength::Long)
+            .as_deref(),
+        Some("British Summer Time")
+    );
+    assert_eq!(
+        tz.display_name(&en, &metazones, winter, NameLength::Long)
+            .as_deref(),
+        Some("Greenwich Mean Time")
+    );
+    assert_eq!(
+        en.display_name(
+            &metazones,
+            "Europe/London",
+            winter,
+            false,
+            NameLength::Short
+        ),
+        Some("GMT")
+    );
+
+    // CLDR names the zone by its former name
+    let metazones = MetaZones::from_xml(
+        r#"<supplementalData><metaZones><metazoneInfo>
+        <timezone type="Asia/Calcutta"><usesMetazone mzone="India"/></timezone>
+        </metazoneInfo></metaZones></supplementalData>"#,
+    )
+    .unwrap();
+    let en = TimeZoneNames::from_xml(
+        r#"<ldml><dates><timeZoneNames>
+        <metazone type="India"><long><standard>India Standard Time</standard></long></metazone>
+        </timeZoneNames></dates></ldml>"#,
+    )
+    .unwrap();
+    #[cfg(target_family = "unix")]
+    let tz = Tz::new("/usr/share/zoneinfo/Asia/Kolkata").unwrap();
+    #[cfg(target_os = "windows")]
+    let tz = Tz::new("c:\\Users\\nbauw\\Dev\\zoneinfo\\Asia\\Kolkata").unwrap();
+    assert_eq!(
+        tz.display_name(&en, &metazones, winter, NameLength::Long),
+        None
+    );
+    let metazones = metazones
+        .with_aliases(
+            r#"<ldmlBCP47><keyword><key name="tz">
+            <type name="inccu" description="Kolkata, India" alias="Asia/Calcutta Asia/Kolkata"/>
+            <type name="unk" description="Unknown time zone"/>
+            </key></keyword></ldmlBCP47>"#,
+        )
+        .unwrap();
+    assert_eq!(metazones.canonical("Asia/Kolkata"), "Asia/Calcutta");
+    assert_eq!(metazones.canonical("Europe/Paris"), "Europe/Paris");
+    assert_eq!(
+        tz.display_name(&en, &metazones, winter, NameLength::Long)
+            .as_deref(),
+        Some("India Standard Time")
+    );
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn worldtimeapi() {
+    #[cfg(target_family = "unix")]
+    let tz = Tz::new("/usr/share/zoneinfo/Europe/Paris").unwrap();
+    #[cfg(target_os = "windows")]
+    let tz = Tz::new("c:\\Users\\nbauw\\Dev\\zoneinfo\\Europe\\Paris").unwrap();
+    let now = Utc.timestamp_opt(1599324104, 279502100).unwrap();
+    let api = tz.zoneinfo_with(&now).unwrap().worldtimeapi("203.0.113.7");
+    assert_eq!(
+        api.to_json().unwrap(),
+        "{\"abbreviation\":\"CEST\",\"client_ip\":\"203.0.113.7\",\"datetime\":\"2020-09-05T18:41:44.279502+02:00\",\
+        \"day_of_week\":6,\"day_of_year\":249,\"dst\":true,\"dst_from\":\"2020-03-29T01:00:00+00:00\",\"dst_offset\":3600,\
+        \"dst_until\":\"2020-10-25T01:00:00+00:00\",\"raw_offset\":3600,\"timezone\":\"Europe/Paris\",\"unixtime\":1599324104,\
+        \"utc_datetime\":\"2020-09-05T16:41:44.279502+00:00\",\"utc_offset\":\"+02:00\",\"week_number\":36}"
+    );
+
+    // Outside DST: no DST period, no DST amount
+    let now = Utc.with_ymd_and_hms(2020, 12, 1, 12, 0, 0).unwrap();
+    let api = tz.zoneinfo_with(&now).unwrap().worldtimeapi("203.0.113.7");
+    assert!(!api.dst);
+    assert_eq!(
+        (api.dst_from, api.dst_until, api.dst_offset),
+        (None, None, 0)
+    );
+    assert_eq!(api.raw_offset, 3600);
+
+    // New Year's Day in Paris, but still the previous year in UTC
+    let now = Utc.with_ymd_and_hms(2019, 12, 31, 23, 30, 0).unwrap();
+    let api = tz.zoneinfo_with(&now).unwrap().worldtimeapi("203.0.113.7");
+    assert_eq!((api.day_of_week, api.day_of_year), (3, 1));
+    assert_eq!(api.unixtime, 1577835000);
+}
+
+#[cfg(all(target_family = "unix", feature = "wasm"))]
+#[test]
+fn wasm_bindings() {
+    let buf = std::fs::read("/usr/share/zoneinfo/Europe/Paris").unwrap();
+    let tz = JsTz::from_bytes(&buf, "Europe/Paris").unwrap();
+    assert_eq!(tz.name(), "Europe/Paris");
+    // 2025-07-01T12:00:00Z
+    let ltt = tz.lookup(1751371200000.0).unwrap();
+    assert_eq!(
+        (ltt.utc_offset, ltt.isdst, ltt.abbreviation.as_str()),
+        (7200, true, "CEST")
+    );
+    assert_eq!(tz.utc_to_local(1751371200000.0).unwrap(), 1751378400000.0);
+    // 2025-03-30T02:30 is skipped, 2025-10-26T02:30 is repeated
+    assert_eq!(
+        tz.local_to_utc(1743301800000.0, Disambiguation::Compatible)
+            .unwrap(),
+        1743298200000.0
+    );
+    assert_eq!(
+        tz.local_to_utc(1761445800000.0, Disambiguation::Later)
+            .unwrap(),
+        1761442200000.0
+    );
+    assert_eq!(
+        tz.format(1751371200000.0, "%H:%M %Z").unwrap(),
+        "14:00 CEST"
+    );
+    let mut transitions = tz.transitions_from(1735689600000.0).unwrap();
+    let spring = transitions.next().unwrap();
+    assert_eq!(
+        (
+            spring.time,
+            spring.new_utc_offset,
+            spring.abbreviation.as_str()
+        ),
+        (1743296400000.0, 7200, "CEST")
+    );
+    assert_eq!(transitions.next().unwrap().time, 1761440400000.0);
+    assert_eq!(
+        tz.previous_transition(1751371200000.0).unwrap(),
+        Some(spring)
+    );
+}
+
+#[cfg(all(target_family = "unix", feature = "capi"))]
+#[test]
+fn capi() {
+    use crate::capi::*;
+    use std::ffi::CStr;
+    let mut tz: *mut Tz = std::ptr::null_mut();
+    let path = c"/usr/share/zoneinfo/Europe/Paris";
+    assert_eq!(unsafe { tz_from_path(path.as_ptr(), &mut tz) }, TZ_OK);
+    let mut ltt = TzLocalTimeType {
+        utc_offset: 0,
+        isdst: false,
+        abbreviation: [0; TZ_ABBREVIATION_SIZE],
+    };
+    assert_eq!(unsafe { tz_lookup(tz, 1751371200, &mut ltt) }, TZ_OK);
+    let abbreviation = unsafe { CStr::from_ptr(ltt.abbreviation.as_ptr()) };
+    assert_eq!(
+        (ltt.utc_offset, ltt.isdst, abbreviation.to_str().unwrap()),
+        (7200, true, "CEST")
+    );
+    let mut t = 0;
+    assert_eq!(
+        unsafe { tz_local_to_utc(tz, 1761445800, TZ_DISAMBIGUATION_REJECT, &mut t) },
+        TZ_ERROR_AMBIGUOUS_LOCAL_TIME
+    );
+    let message = unsafe { CStr::from_ptr(tz_strerror(TZ_ERROR_AMBIGUOUS_LOCAL_TIME)) };
+    assert_eq!(message.to_str().unwrap(), "Ambiguous local time");
+    unsafe { tz_free(tz) };
+    // Panics are not unwound into C
+    assert_eq!(
+        crate::capi::write(&mut t, || panic!("bug")),
+        TZ_ERROR_INTERNAL
+    );
+}
+
+#[cfg(all(target_family = "unix", feature = "python"))]
+#[test]
+fn python_bindings() {
+    use pyo3::{prelude::*, wrap_pymodule};
+    Python::initialize();
+    Python::attach(|py| {
+        let module = wrap_pymodule!(crate::python::libtzfile)(py);
+        let modules = py.import("sys").unwrap().getattr("modules").unwrap();
+        modules.set_item("libtzfile", module).unwrap();
+        py.run(
+            cr#"
+import datetime, libtzfile
+tz = libtzfile.Tz("/usr/share/zoneinfo/Europe/Paris")
+assert [t.abbreviation for t in tz.transition_times(2020)] == ["CEST", "CET"]
+now = datetime.datetime(2020, 9, 5, 16, 41, 44, tzinfo=datetime.timezone.utc)
+assert tz.zoneinfo(now).dst_until == datetime.datetime(2020, 10, 25, 1, tzinfo=datetime.timezone.utc)
+assert tz.lookup(now).utc_offset == 7200
+assert tz.local_to_utc(datetime.datetime(2025, 10, 26, 2, 30), "later").hour == 1
+try:
+    tz.local_to_utc(datetime.datetime(2025, 10, 26, 2, 30), "reject")
+    assert False
+except libtzfile.TzfileError:
+    pass
+zone = tz.tzinfo()
+assert isinstance(zone, datetime.tzinfo)
+earlier = datetime.datetime(2025, 10, 26, 2, 30, tzinfo=zone)
+later = earlier.replace(fold=1)
+assert (earlier.tzname(), later.tzname()) == ("CEST", "CET")
+assert earlier.dst() == datetime.timedelta(hours=1) and later.dst() == datetime.timedelta(0)
+utc = datetime.datetime(2025, 10, 26, 1, 30, tzinfo=datetime.timezone.utc)
+local = utc.astimezone(zone)
+assert (local.hour, local.fold, local.tzname()) == (2, 1, "CET")
+try:
+    zone.fromutc(utc.replace(tzinfo=tz.tzinfo()))
+    assert False
+except ValueError:
+    pass
+"#,
+            None,
+            None,
+        )
+        .unwrap();
+    });
+}
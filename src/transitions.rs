@@ -0,0 +1,208 @@
+//! Lazy iteration over a zone's transitions (available with the parse or json features).
+
+use crate::{posix, String, ToString, Tz, BIG_BANG};
+use chrono::{DateTime, TimeZone, Utc};
+
+/// One transition of a zone, as yielded by the Transitions iterator.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+pub struct Transition {
+    /// The UTC time and date of the transition
+    pub time: DateTime<Utc>,
+    /// The offset to UTC BEFORE the transition
+    pub old_utc_offset: isize,
+    /// The offset to UTC AFTER the transition
+    pub new_utc_offset: isize,
+    /// Is DST observed after the transition ?
+    pub isdst: bool,
+    /// TZ abbreviation after the transition
+    pub abbreviation: String,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Cursor {
+    // Index in the recorded transition times table
+    Recorded(usize),
+    // Year and slot of the transitions generated from the footer
+    Footer(i32, usize),
+    Done,
+}
+
+/// Forward iterator over a zone's transitions, returned by ```Tz::transitions_from```.
+///
+/// It walks forward from the requested instant (included), through the recorded transition times
+/// and then indefinitely through the ones generated from the TZ string footer. Having no end, it does not implement
+/// DoubleEndedIterator: the backward walk is ```TransitionsBefore```.
+#[derive(Debug, Clone)]
+pub struct Transitions<'a> {
+    tz: &'a Tz,
+    instant: i64,
+    cursor: Cursor,
+}
+
+impl<'a> Transitions<'a> {
+    pub(crate) fn new(tz: &'a Tz, instant: DateTime<Utc>) -> Transitions<'a> {
+        let instant = ceiling(instant);
+        Transitions {
+            tz,
+            instant,
+            cursor: Cursor::Recorded(tz.tzh_timecnt_data.partition_point(|&t| t < instant)),
+        }
+    }
+}
+
+/// Backward iterator over a zone's transitions, returned by ```Tz::transitions_before```.
+///
+/// It walks backward from the requested instant (excluded), the most recent transition first,
+/// down to the first recorded transition time.
+#[derive(Debug, Clone)]
+pub struct TransitionsBefore<'a> {
+    tz: &'a Tz,
+    instant: i64,
+    cursor: Cursor,
+}
+
+impl<'a> TransitionsBefore<'a> {
+    pub(crate) fn new(tz: &'a Tz, instant: DateTime<Utc>) -> TransitionsBefore<'a> {
+        let instant = ceiling(instant);
+        let cursor = match (&tz.tz_footer, tz.tzh_timecnt_data.last()) {
+            (Some(_), Some(&last)) if instant > last => Cursor::Footer(posix::year_of(instant), 1),
+            (Some(_), None) => Cursor::Footer(posix::year_of(instant), 1),
+            _ => Cursor::Recorded(tz.tzh_timecnt_data.partition_point(|&t| t < instant)),
+        };
+        TransitionsBefore {
+            tz,
+            instant,
+            cursor,
+        }
+    }
+}
+
+// Transition times are whole seconds: the first one at or after the instant is at or after its ceiling
+fn ceiling(instant: DateTime<Utc>) -> i64 {
+    instant.timestamp() + i64::from(instant.timestamp_subsec_nanos() > 0)
+}
+
+// None for the "big bang" transition time, which is always the first one
+fn recorded(tz: &Tz, i: usize) -> Option<Transition> {
+    let t = tz.tzh_timecnt_data[i];
+    if t == BIG_BANG {
+        return None;
+    }
+    let ttinfo = tz.ttinfo_at(Some(i));
+    Some(Transition {
+        time: Utc.timestamp_opt(t, 0).single()?,
+        old_utc_offset: tz.ttinfo_at(i.checked_sub(1)).tt_utoff,
+        new_utc_offset: ttinfo.tt_utoff,
+        isdst: ttinfo.tt_isdst == 1,
+        abbreviation: tz.tz_abbr[ttinfo.tt_abbrind as usize].clone(),
+    })
+}
+
+fn generated(tz: &Tz, t: i64, isdst: bool) -> Option<Transition> {
+    let footer = tz.tz_footer.as_ref()?;
+    let (new_utc_offset, isdst, abbreviation) = footer.ttinfo(isdst);
+    Some(Transition {
+        time: Utc.timestamp_opt(t, 0).single()?,
+        old_utc_offset: tz.local_type_at(t - 1).0,
+        new_utc_offset,
+        isdst,
+        abbreviation: abbreviation.to_string(),
+    })
+}
+
+// Footer transition at the given year and slot, None if the footer has no DST rule
+fn footer_transition(tz: &Tz, year: i32, slot: usize) -> Option<(i64, bool)> {
+    tz.tz_footer.as_ref()?.transitions(year)[slot]
+}
+
+impl Iterator for Transitions<'_> {
+    type Item = Transition;
+
+    fn next(&mut self) -> Option<Transition> {
+        let recorded_end = self.tz.tzh_timecnt_data.last().copied();
+        loop {
+            match self.cursor {
+                Cursor::Recorded(i) if i < self.tz.tzh_timecnt_data.len() => {
+                    self.cursor = Cursor::Recorded(i + 1);
+                    if let Some(tt) = recorded(self.tz, i) {
+                        return Some(tt);
+                    }
+                }
+                Cursor::Recorded(_) => {
+                    let from = recorded_end.map_or(self.instant, |t| t.max(self.instant));
+                    self.cursor = Cursor::Footer(posix::year_of(from), 0);
+                }
+                Cursor::Footer(year, slot) => {
+                    let (t, isdst) = match footer_transition(self.tz, year, slot) {
+                        Some(t) => t,
+                        // No DST rule: no more transitions
+                        None => {
+                            self.cursor = Cursor::Done;
+                            return None;
+                        }
+                    };
+                    self.cursor = if slot == 0 {
+                        Cursor::Footer(year, 1)
+                    } else {
+                        Cursor::Footer(year + 1, 0)
+                    };
+                    if t >= self.instant && recorded_end.is_none_or(|r| t > r) {
+                        let tt = generated(self.tz, t, isdst);
+                        if tt.is_none() {
+                            self.cursor = Cursor::Done;
+                        }
+                        return tt;
+                    }
+                }
+                Cursor::Done => return None,
+            }
+        }
+    }
+}
+
+impl Iterator for TransitionsBefore<'_> {
+    type Item = Transition;
+
+    fn next(&mut self) -> Option<Transition> {
+        let recorded_end = self.tz.tzh_timecnt_data.last().copied();
+        loop {
+            match self.cursor {
+                Cursor::Recorded(i) if i > 0 => {
+                    self.cursor = Cursor::Recorded(i - 1);
+                    match recorded(self.tz, i - 1) {
+                        Some(tt) => return Some(tt),
+                        // Nothing before the "big bang"
+                        None => {
+                            self.cursor = Cursor::Done;
+                            return None;
+                        }
+                    }
+                }
+                Cursor::Footer(year, slot) => {
+                    let (t, isdst) = match footer_transition(self.tz, year, slot) {
+                        Some(t) if recorded_end.is_none_or(|r| t.0 > r) => t,
+                        // Back to the recorded transition times
+                        _ => {
+                            self.cursor = Cursor::Recorded(self.tz.tzh_timecnt_data.len());
+                            continue;
+                        }
+                    };
+                    self.cursor = if slot == 1 {
+                        Cursor::Footer(year, 0)
+                    } else {
+                        Cursor::Footer(year - 1, 1)
+                    };
+                    if t < self.instant {
+                        let tt = generated(self.tz, t, isdst);
+                        if tt.is_none() {
+                            self.cursor = Cursor::Done;
+                        }
+                        return tt;
+                    }
+                }
+                Cursor::Recorded(_) | Cursor::Done => return None,
+            }
+        }
+    }
+}
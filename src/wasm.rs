@@ -0,0 +1,181 @@
+//! WebAssembly bindings, with wasm-bindgen (available with the wasm feature, which includes the parse feature).
+//!
+//! Instants are numbers of milliseconds since the Unix epoch, as JavaScript's ```Date.getTime()```, and local
+//! times are given the same way, as if the local time were UTC (ie. ```Date.UTC(2025, 2, 30, 2, 30)```).
+//!
+//! ```text
+//! import init, { Tz, Disambiguation } from "./libtzfile.js";
+//! await init();
+//! const buf = new Uint8Array(await (await fetch("/zoneinfo/Europe/Paris")).arrayBuffer());
+//! const tz = Tz.fromBytes(buf, "Europe/Paris");
+//! const ltt = tz.lookup(Date.now());
+//! const utc = tz.localToUtc(Date.UTC(2025, 2, 30, 2, 30), Disambiguation.Compatible);
+//! const transitions = tz.transitionsFrom(Date.UTC(2025, 0, 1));
+//! for (let tt = transitions.next(); tt.time < Date.UTC(2026, 0, 1); tt = transitions.next()) {
+//!     console.log(new Date(tt.time), tt.abbreviation);
+//! }
+//! ```
+
+use crate::{Disambiguation, String, ToString, Transition, Tz, TzError};
+use chrono::{DateTime, NaiveDateTime, TimeDelta, Utc};
+use std::rc::Rc;
+use wasm_bindgen::prelude::*;
+
+/// A zone, exported to JavaScript as ```Tz```.
+#[wasm_bindgen(js_name = Tz)]
+#[derive(Debug, Clone)]
+pub struct JsTz {
+    tz: Rc<Tz>,
+    name: String,
+}
+
+/// Local time type in effect at an instant, exported to JavaScript as ```LocalTimeType```.
+#[wasm_bindgen(js_name = LocalTimeType, getter_with_clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsLocalTimeType {
+    /// Offset to UTC, in seconds
+    #[wasm_bindgen(js_name = utcOffset)]
+    pub utc_offset: i32,
+    /// Is DST observed ?
+    pub isdst: bool,
+    /// TZ abbreviation
+    pub abbreviation: String,
+}
+
+/// One transition of a zone, exported to JavaScript as ```Transition```.
+#[wasm_bindgen(js_name = Transition, getter_with_clone)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct JsTransition {
+    /// Time of the transition, in milliseconds since the Unix epoch
+    pub time: f64,
+    /// The offset to UTC BEFORE the transition
+    #[wasm_bindgen(js_name = oldUtcOffset)]
+    pub old_utc_offset: i32,
+    /// The offset to UTC AFTER the transition
+    #[wasm_bindgen(js_name = newUtcOffset)]
+    pub new_utc_offset: i32,
+    /// Is DST observed after the transition ?
+    pub isdst: bool,
+    /// TZ abbreviation after the transition
+    pub abbreviation: String,
+}
+
+/// Forward iterator over a zone's transitions, exported to JavaScript as ```Transitions```.
+#[wasm_bindgen(js_name = Transitions)]
+#[derive(Debug, Clone)]
+pub struct JsTransitions {
+    tz: Rc<Tz>,
+    from: DateTime<Utc>,
+}
+
+#[wasm_bindgen(js_class = Tz)]
+impl JsTz {
+    /// Parses a TZfile from its content. The name is the zone's name (ie. Europe/Paris).
+    #[wasm_bindgen(js_name = fromBytes)]
+    pub fn from_bytes(buf: &[u8], name: &str) -> Result<JsTz, JsError> {
+        Ok(JsTz {
+            tz: Rc::new(Tz::from_bytes(buf, name)?),
+            name: name.to_string(),
+        })
+    }
+
+    /// Zone's name
+    #[wasm_bindgen(getter)]
+    pub fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    /// Returns the local time type (offset to UTC, DST and abbreviation) in effect at an instant.
+    pub fn lookup(&self, time: f64) -> Result<JsLocalTimeType, JsError> {
+        let ltt = self.tz.lookup(instant(time)?);
+        Ok(JsLocalTimeType {
+            utc_offset: ltt.utc_offset as i32,
+            isdst: ltt.isdst,
+            abbreviation: ltt.abbreviation,
+        })
+    }
+
+    /// Converts an instant to the local time, given as if it were UTC.
+    #[wasm_bindgen(js_name = utcToLocal)]
+    pub fn utc_to_local(&self, time: f64) -> Result<f64, JsError> {
+        let instant = instant(time)?;
+        let offset = TimeDelta::seconds(self.tz.lookup(instant).utc_offset as i64);
+        let local = instant.checked_add_signed(offset).ok_or(TzError::NoData)?;
+        Ok(milliseconds(local))
+    }
+
+    /// Converts a local time, given as if it were UTC, to an instant. Ambiguous and skipped local times are handled
+    /// according to the disambiguation policy.
+    #[wasm_bindgen(js_name = localToUtc)]
+    pub fn local_to_utc(&self, local: f64, disambiguation: Disambiguation) -> Result<f64, JsError> {
+        let local: NaiveDateTime = instant(local)?.naive_utc();
+        Ok(milliseconds(self.tz.local_to_utc(local, disambiguation)?))
+    }
+
+    /// Formats an instant in the zone's local time, as ```Tz::format```.
+    pub fn format(&self, time: f64, fmt: &str) -> Result<String, JsError> {
+        Ok(self.tz.format(instant(time)?, fmt)?)
+    }
+
+    /// Returns an iterator over the transitions at or after an instant, recorded or generated from the footer.
+    #[wasm_bindgen(js_name = transitionsFrom)]
+    pub fn transitions_from(&self, time: f64) -> Result<JsTransitions, JsError> {
+        Ok(JsTransitions {
+            tz: Rc::clone(&self.tz),
+            from: instant(time)?,
+        })
+    }
+
+    /// Returns the first transition changing the local time type strictly after an instant.
+    #[wasm_bindgen(js_name = nextTransition)]
+    pub fn next_transition(&self, time: f64) -> Result<Option<JsTransition>, JsError> {
+        Ok(self
+            .tz
+            .next_transition(instant(time)?)
+            .map(JsTransition::from))
+    }
+
+    /// Returns the last transition changing the local time type strictly before an instant.
+    #[wasm_bindgen(js_name = previousTransition)]
+    pub fn previous_transition(&self, time: f64) -> Result<Option<JsTransition>, JsError> {
+        Ok(self
+            .tz
+            .previous_transition(instant(time)?)
+            .map(JsTransition::from))
+    }
+}
+
+#[wasm_bindgen(js_class = Transitions)]
+impl JsTransitions {
+    /// Returns the next transition, or undefined at the end of the recorded transitions of a zone without footer.
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Option<JsTransition> {
+        let tt = self.tz.transitions_from(self.from).next()?;
+        self.from = tt.time + TimeDelta::seconds(1);
+        Some(JsTransition::from(tt))
+    }
+}
+
+impl From<Transition> for JsTransition {
+    fn from(tt: Transition) -> JsTransition {
+        JsTransition {
+            time: milliseconds(tt.time),
+            old_utc_offset: tt.old_utc_offset as i32,
+            new_utc_offset: tt.new_utc_offset as i32,
+            isdst: tt.isdst,
+            abbreviation: tt.abbreviation,
+        }
+    }
+}
+
+// Instant of a number of milliseconds since the Unix epoch
+fn instant(time: f64) -> Result<DateTime<Utc>, TzError> {
+    if !time.is_finite() {
+        return Err(TzError::NoData);
+    }
+    DateTime::from_timestamp_millis(time.floor() as i64).ok_or(TzError::NoData)
+}
+
+fn milliseconds(instant: DateTime<Utc>) -> f64 {
+    instant.timestamp_millis() as f64
+}
@@ -0,0 +1,116 @@
+//! Windows time zone IDs to IANA zone names mapping, from the CLDR windowsZones.xml file (available with the parse or json features).
+
+use crate::{xml, String, ToString, TzError, Vec};
+use std::{fs, path::Path, sync::OnceLock};
+
+// Snapshot of the CLDR 42 file
+const BUNDLED: &str = include_str!("../data/windowsZones.xml");
+
+/// One mapping of the windowsZones.xml file: a Windows time zone ID and the IANA zones it stands for in a territory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MapZone {
+    /// Windows time zone ID (ie. W. Europe Standard Time)
+    pub windows_id: String,
+    /// Territory (ISO 3166 code), 001 being the default mapping of the Windows ID
+    pub territory: String,
+    /// IANA zone names, the first one being the main zone of the territory
+    pub zones: Vec<String>,
+}
+
+/// Windows time zone IDs to IANA zone names mapping, as published by CLDR in windowsZones.xml.
+/// A snapshot (CLDR 42) is bundled, a more recent file can be read with ```from_file```.
+///
+/// ```rust
+/// use libtzfile::WindowsZones;
+/// let windows_zones = WindowsZones::bundled();
+/// println!("{:?}", windows_zones.to_iana("W. Europe Standard Time", None));
+/// println!("{:?}", windows_zones.to_iana("Romance Standard Time", Some("FR")));
+/// println!("{:?}", windows_zones.to_windows("America/Chicago"));
+/// ```
+///
+/// ```text
+/// Some("Europe/Berlin")
+/// Some("Europe/Paris")
+/// Some("Central Standard Time")
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WindowsZones {
+    map: Vec<MapZone>,
+}
+
+impl Default for WindowsZones {
+    fn default() -> WindowsZones {
+        WindowsZones::bundled().clone()
+    }
+}
+
+impl WindowsZones {
+    /// Returns the bundled mapping (parsed once, then shared).
+    pub fn bundled() -> &'static WindowsZones {
+        static PARSED: OnceLock<WindowsZones> = OnceLock::new();
+        PARSED.get_or_init(|| WindowsZones::from_xml(BUNDLED).unwrap())
+    }
+
+    /// Reads the mapping from the content of a windowsZones.xml file.
+    pub fn from_xml(xml: &str) -> Result<WindowsZones, TzError> {
+        let mut map = Vec::new();
+        for event in xml::parse(xml)? {
+            if !matches!(event, xml::Event::Start("mapZone", _)) {
+                continue;
+            }
+            let attribute = |name| {
+                event
+                    .attribute(name)
+                    .map(|v| v.to_string())
+                    .ok_or(TzError::ParseError)
+            };
+            map.push(MapZone {
+                windows_id: attribute("other")?,
+                territory: attribute("territory")?,
+                zones: attribute("type")?
+                    .split_whitespace()
+                    .map(|z| z.to_string())
+                    .collect(),
+            });
+        }
+        if map.is_empty() {
+            return Err(TzError::NoData);
+        }
+        Ok(WindowsZones { map })
+    }
+
+    /// Reads the mapping from a windowsZones.xml file (ie. from a CLDR release).
+    pub fn from_file(path: impl AsRef<Path>) -> Result<WindowsZones, TzError> {
+        WindowsZones::from_xml(&fs::read_to_string(path)?)
+    }
+
+    /// All the mappings, in the file's order.
+    pub fn mappings(&self) -> &[MapZone] {
+        &self.map
+    }
+
+    /// Returns the IANA zone of a Windows time zone ID in a territory, both compared case-insensitively.
+    /// Without a territory, or if the Windows ID has no mapping for it, the default (001) zone is returned.
+    pub fn to_iana(&self, windows_id: &str, territory: Option<&str>) -> Option<&str> {
+        let zone = |territory: &str| {
+            self.map
+                .iter()
+                .find(|m| {
+                    m.windows_id.eq_ignore_ascii_case(windows_id)
+                        && m.territory.eq_ignore_ascii_case(territory)
+                })
+                .and_then(|m| m.zones.first())
+                .map(|z| z.as_str())
+        };
+        territory.and_then(zone).or_else(|| zone("001"))
+    }
+
+    /// Returns the Windows time zone ID of an IANA zone. The zone names are CLDR's, which may be former names
+    /// (ie. Asia/Calcutta): ```ZoneLoader::windows_id``` also finds the links.
+    pub fn to_windows(&self, zone: &str) -> Option<&str> {
+        self.map
+            .iter()
+            .find(|m| m.zones.iter().any(|z| z == zone))
+            .map(|m| m.windows_id.as_str())
+    }
+}
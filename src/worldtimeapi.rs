@@ -0,0 +1,78 @@
+//! worldtimeapi.org response schema (available with the json feature).
+
+use crate::{String, ToString, Tzinfo};
+use chrono::Datelike;
+use serde::{Deserialize, Serialize};
+
+/// The current time in a zone, with exactly the fields, names and formats of a worldtimeapi.org response,
+/// as returned by ```Tzinfo::worldtimeapi```.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WorldTimeApi {
+    /// Timezone abbreviation
+    pub abbreviation: String,
+    /// IP address of the client
+    pub client_ip: String,
+    /// Local time, in RFC 3339 format with microseconds
+    pub datetime: String,
+    /// Local day of the week, from 0 (Sunday) to 6
+    pub day_of_week: u32,
+    /// Local day of the year, from 1
+    pub day_of_year: u32,
+    /// Are we in DST period ?
+    pub dst: bool,
+    /// Start of the current DST period, None outside DST
+    pub dst_from: Option<String>,
+    /// DST amount currently added to the normal offset, in seconds (0 outside DST)
+    pub dst_offset: isize,
+    /// End of the current DST period, None outside DST
+    pub dst_until: Option<String>,
+    /// Normal offset to UTC, in seconds
+    pub raw_offset: isize,
+    /// Timezone name
+    pub timezone: String,
+    /// Seconds since the Unix epoch
+    pub unixtime: i64,
+    /// UTC time, in RFC 3339 format with microseconds
+    pub utc_datetime: String,
+    /// Current offset to UTC, in +/-HH:MM
+    pub utc_offset: String,
+    /// Week number
+    pub week_number: i32,
+}
+
+impl WorldTimeApi {
+    /// Transforms the WorldTimeApi struct to a JSON string
+    pub fn to_json(&self) -> Result<String, serde_json::error::Error> {
+        serde_json::to_string(self)
+    }
+}
+
+pub(crate) fn worldtimeapi(tzinfo: &Tzinfo, client_ip: &str) -> WorldTimeApi {
+    let dst_time = |t: Option<chrono::DateTime<chrono::Utc>>| {
+        t.filter(|_| tzinfo.dst_period)
+            .map(|t| t.format("%Y-%m-%dT%H:%M:%S+00:00").to_string())
+    };
+    WorldTimeApi {
+        abbreviation: tzinfo.abbreviation.clone(),
+        client_ip: client_ip.to_string(),
+        datetime: tzinfo
+            .datetime
+            .format("%Y-%m-%dT%H:%M:%S%.6f%:z")
+            .to_string(),
+        day_of_week: tzinfo.datetime.weekday().num_days_from_sunday(),
+        day_of_year: tzinfo.datetime.ordinal(),
+        dst: tzinfo.dst_period,
+        dst_from: dst_time(tzinfo.dst_from),
+        dst_offset: tzinfo.utc_offset.local_minus_utc() as isize - tzinfo.raw_offset,
+        dst_until: dst_time(tzinfo.dst_until),
+        raw_offset: tzinfo.raw_offset,
+        timezone: tzinfo.timezone.clone(),
+        unixtime: tzinfo.utc_datetime.timestamp(),
+        utc_datetime: tzinfo
+            .utc_datetime
+            .format("%Y-%m-%dT%H:%M:%S%.6f+00:00")
+            .to_string(),
+        utc_offset: tzinfo.datetime.format("%:z").to_string(),
+        week_number: tzinfo.week_number,
+    }
+}
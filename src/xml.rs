@@ -0,0 +1,143 @@
+//! Minimal XML reader for the CLDR data files (available with the parse or json features).
+//! It handles elements, attributes, text, comments, CDATA sections and the predefined and numeric entities:
+//! enough for the supplemental and locale files, which have no internal DTD subset.
+
+use crate::{String, ToString, TzError, Vec};
+
+pub(crate) type Attributes<'a> = Vec<(&'a str, String)>;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Event<'a> {
+    // Element name and attributes (an empty element is followed by its End event)
+    Start(&'a str, Attributes<'a>),
+    End(&'a str),
+    // Text between tags, unless it is only whitespace
+    Text(String),
+}
+
+impl Event<'_> {
+    // Value of an attribute of a Start event
+    pub(crate) fn attribute(&self, name: &str) -> Option<&str> {
+        match self {
+            Event::Start(_, attributes) => attributes
+                .iter()
+                .find(|(n, _)| *n == name)
+                .map(|(_, v)| v.as_str()),
+            _ => None,
+        }
+    }
+}
+
+// Reads a whole document, checking that the elements are properly nested
+pub(crate) fn parse(xml: &str) -> Result<Vec<Event<'_>>, TzError> {
+    let mut events = Vec::new();
+    let mut open: Vec<&str> = Vec::new();
+    let mut rest = xml;
+    while let Some(i) = rest.find('<') {
+        text(&rest[..i], &mut events)?;
+        rest = &rest[i..];
+        if let Some(r) = rest.strip_prefix("<!--") {
+            rest = after(r, "-->")?;
+        } else if let Some(r) = rest.strip_prefix("<![CDATA[") {
+            let end = r.find("]]>").ok_or(TzError::ParseError)?;
+            events.push(Event::Text(r[..end].to_string()));
+            rest = &r[end + 3..];
+        } else if rest.starts_with("<?") {
+            rest = after(rest, "?>")?;
+        } else if rest.starts_with("<!") {
+            rest = after(rest, ">")?;
+        } else if let Some(r) = rest.strip_prefix("</") {
+            let end = r.find('>').ok_or(TzError::ParseError)?;
+            let name = r[..end].trim();
+            if open.pop() != Some(name) {
+                return Err(TzError::ParseError);
+            }
+            events.push(Event::End(name));
+            rest = &r[end + 1..];
+        } else {
+            let (name, attributes, empty, r) = element(&rest[1..])?;
+            events.push(Event::Start(name, attributes));
+            if empty {
+                events.push(Event::End(name));
+            } else {
+                open.push(name);
+            }
+            rest = r;
+        }
+    }
+    text(rest, &mut events)?;
+    if !open.is_empty() {
+        return Err(TzError::ParseError);
+    }
+    Ok(events)
+}
+
+fn after<'a>(s: &'a str, pattern: &str) -> Result<&'a str, TzError> {
+    let end = s.find(pattern).ok_or(TzError::ParseError)?;
+    Ok(&s[end + pattern.len()..])
+}
+
+fn text(s: &str, events: &mut Vec<Event<'_>>) -> Result<(), TzError> {
+    if !s.trim().is_empty() {
+        events.push(Event::Text(unescape(s)?));
+    }
+    Ok(())
+}
+
+// Name, attributes and emptiness of an element, and what follows its start tag
+fn element(s: &str) -> Result<(&str, Attributes<'_>, bool, &str), TzError> {
+    let end = s
+        .find(|c: char| c.is_whitespace() || c == '/' || c == '>')
+        .ok_or(TzError::ParseError)?;
+    let name = &s[..end];
+    if name.is_empty() {
+        return Err(TzError::ParseError);
+    }
+    let mut attributes = Vec::new();
+    let mut rest = s[end..].trim_start();
+    loop {
+        if let Some(r) = rest.strip_prefix("/>") {
+            return Ok((name, attributes, true, r));
+        }
+        if let Some(r) = rest.strip_prefix('>') {
+            return Ok((name, attributes, false, r));
+        }
+        let (attribute, r) = rest.split_once('=').ok_or(TzError::ParseError)?;
+        let r = r.trim_start();
+        let quote = r.chars().next().filter(|&c| c == '"' || c == '\'');
+        let quote = quote.ok_or(TzError::ParseError)?;
+        let (value, r) = r[1..].split_once(quote).ok_or(TzError::ParseError)?;
+        attributes.push((attribute.trim(), unescape(value)?));
+        rest = r.trim_start();
+    }
+}
+
+fn unescape(s: &str) -> Result<String, TzError> {
+    let mut unescaped = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(i) = rest.find('&') {
+        unescaped.push_str(&rest[..i]);
+        let (entity, r) = rest[i + 1..].split_once(';').ok_or(TzError::ParseError)?;
+        let c = match entity {
+            "amp" => '&',
+            "lt" => '<',
+            "gt" => '>',
+            "quot" => '"',
+            "apos" => '\'',
+            _ => {
+                let code = match entity.strip_prefix("#x") {
+                    Some(hex) => u32::from_str_radix(hex, 16)?,
+                    None => entity
+                        .strip_prefix('#')
+                        .ok_or(TzError::ParseError)?
+                        .parse()?,
+                };
+                char::from_u32(code).ok_or(TzError::ParseError)?
+            }
+        };
+        unescaped.push(c);
+        rest = r;
+    }
+    unescaped.push_str(rest);
+    Ok(unescaped)
+}
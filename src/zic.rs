@@ -0,0 +1,407 @@
+//! zic source export (available with the parse or json features).
+//!
+//! The eras of the zone (```Tz::eras```) become the Zone lines. The transitions of the eras observing DST become Rule
+//! lines, folded over consecutive years when they share the month, day rule, time, save amount and letter, and the TZ
+//! string footer becomes the rules without end year (```max```) of the last Zone line.
+
+use crate::eras::{self, Format, Segment, Segmentation};
+use crate::{posix, PosixDate, PosixTz, String, ToString, Tz, TzError, Vec};
+use chrono::{DateTime, Datelike, NaiveDate, NaiveDateTime, Timelike, Utc};
+use std::format;
+
+const MONTHS: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+const WEEKDAYS: [&str; 7] = ["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"];
+
+// RULES field of a Zone line
+enum Rules {
+    None,
+    Save(isize),
+    Named,
+}
+
+// ON field of a Rule line
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Day {
+    // Weekday
+    Last(u32),
+    // Weekday, day of the month
+    OnOrAfter(u32, u32),
+    Fixed(u32),
+}
+
+impl core::fmt::Display for Day {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match *self {
+            Day::Last(weekday) => write!(f, "last{}", WEEKDAYS[weekday as usize]),
+            Day::OnOrAfter(weekday, day) => write!(f, "{}>={}", WEEKDAYS[weekday as usize], day),
+            Day::Fixed(day) => write!(f, "{}", day),
+        }
+    }
+}
+
+// A Rule line, or the occurrence of a transition (FROM and TO being its year) before folding
+struct Rule {
+    // None: min
+    from: Option<i32>,
+    // None: max
+    to: Option<i32>,
+    month: u32,
+    // Day rules matching all the years, by order of preference
+    days: Vec<Day>,
+    // Time of day, and its suffix (wall clock time when empty, u for UTC)
+    at: i64,
+    suffix: &'static str,
+    save: isize,
+    isdst: bool,
+    letter: String,
+}
+
+impl Rule {
+    // The transition at a (wall clock or UTC) time, as the occurrence of a rule
+    fn occurrence(
+        time: i64,
+        suffix: &'static str,
+        save: isize,
+        isdst: bool,
+        letter: String,
+    ) -> Result<Rule, TzError> {
+        let time = DateTime::<Utc>::from_timestamp(time, 0)
+            .ok_or(TzError::NoData)?
+            .naive_utc();
+        let (day, weekday) = (time.day(), time.weekday().num_days_from_sunday());
+        let mut days = Vec::new();
+        if day as i64 + 7 > posix::days_in_month(time.year(), time.month()) {
+            days.push(Day::Last(weekday));
+        }
+        // The usual lower bounds (1, 8, 15, 22) come first
+        let usual = 1 + 7 * ((day - 1) / 7);
+        days.push(Day::OnOrAfter(weekday, usual));
+        days.extend(
+            (day.saturating_sub(6).max(1)..=day)
+                .filter(|&d| d != usual)
+                .map(|d| Day::OnOrAfter(weekday, d)),
+        );
+        days.push(Day::Fixed(day));
+        Ok(Rule {
+            from: Some(time.year()),
+            to: Some(time.year()),
+            month: time.month(),
+            days,
+            at: time.num_seconds_from_midnight() as i64,
+            suffix,
+            save,
+            isdst,
+            letter,
+        })
+    }
+
+    // Same month, time, save amount and letter
+    fn recurs(&self, other: &Rule) -> bool {
+        (
+            self.month,
+            self.at,
+            self.suffix,
+            self.save,
+            self.isdst,
+            &self.letter,
+        ) == (
+            other.month,
+            other.at,
+            other.suffix,
+            other.save,
+            other.isdst,
+            &other.letter,
+        )
+    }
+
+    fn day(&self) -> Day {
+        match self.days[..] {
+            [.., Day::Fixed(day)] if self.from == self.to => Day::Fixed(day),
+            _ => self.days[0],
+        }
+    }
+}
+
+pub(crate) fn zic_source(tz: &Tz) -> Result<String, TzError> {
+    let Segmentation {
+        periods,
+        segments,
+        footer,
+    } = eras::segmentation(tz)?;
+
+    // Occurrences of the rules. When a Zone line starts, zic takes the state of the rule which occurred last: a rule
+    // occurring at the start is added when that state is not the one of the first period.
+    let name = rule_name(&tz.name);
+    let mut occurrences = Vec::new();
+    let mut state: Option<(isize, bool, String)> = None;
+    for era in segments
+        .iter()
+        .filter(|s| matches!(zone_rules(s), Rules::Named))
+    {
+        let format = era.format();
+        for i in era.periods.clone() {
+            let p = &periods[i];
+            let Some((time, old_utc_offset)) = p.start else {
+                continue;
+            };
+            let save = p.utc_offset - p.std_offset;
+            let current = (save, p.isdst, format.letter(&p.abbreviation));
+            let occurrence = if i > era.periods.start {
+                Rule::occurrence(
+                    time + old_utc_offset as i64,
+                    "",
+                    save,
+                    p.isdst,
+                    current.2.clone(),
+                )?
+            } else if state.as_ref() != Some(&current) {
+                Rule::occurrence(time, "u", save, p.isdst, current.2.clone())?
+            } else {
+                continue;
+            };
+            occurrences.push(occurrence);
+            state = Some(current);
+        }
+    }
+    let mut rules = fold(occurrences);
+    if let (Some((footer, from)), Some(era)) = (footer, segments.last()) {
+        for rule in footer_rules(footer, from, &era.format()) {
+            // The recorded transitions of the previous years may follow the same rule
+            let previous = rules.iter().position(|r| {
+                r.recurs(&rule)
+                    && r.to.zip(from).is_some_and(|(to, from)| to + 1 == from)
+                    && r.days.contains(&rule.days[0])
+            });
+            match previous {
+                Some(i) => {
+                    let previous = rules.remove(i);
+                    rules.push(Rule {
+                        from: previous.from,
+                        ..rule
+                    });
+                }
+                None => rules.push(rule),
+            }
+        }
+    }
+    rules.sort_by_key(|r| {
+        (
+            r.from.unwrap_or(i32::MIN),
+            r.month,
+            r.days.last().copied().map(|d| match d {
+                Day::Fixed(day) => day,
+                _ => 0,
+            }),
+        )
+    });
+
+    let mut zic = String::new();
+    if !rules.is_empty() {
+        zic.push_str("# Rule\tNAME\tFROM\tTO\t-\tIN\tON\tAT\tSAVE\tLETTER/S\n");
+    }
+    for rule in &rules {
+        let to = match rule.to {
+            None => String::from("max"),
+            Some(_) if rule.to == rule.from => String::from("only"),
+            Some(to) => to.to_string(),
+        };
+        zic.push_str(&format!(
+            "Rule\t{}\t{}\t{}\t-\t{}\t{}\t{}{}\t{}\t{}\n",
+            name,
+            rule.from.map_or(String::from("min"), |y| y.to_string()),
+            to,
+            MONTHS[rule.month as usize - 1],
+            rule.day(),
+            hms(rule.at),
+            rule.suffix,
+            save(rule.save, rule.isdst),
+            rule.letter
+        ));
+    }
+    zic.push_str("# Zone\tNAME\tSTDOFF\tRULES\tFORMAT\t[UNTIL]\n");
+    for (i, era) in segments.iter().enumerate() {
+        let rules = match zone_rules(era) {
+            Rules::None => String::from("-"),
+            Rules::Save(s) => save(s, true),
+            Rules::Named => name.clone(),
+        };
+        if i == 0 {
+            zic.push_str(&format!("Zone\t{}\t", tz.name));
+        } else {
+            zic.push_str("\t\t\t");
+        }
+        zic.push_str(&format!(
+            "{}\t{}\t{}",
+            hms(era.std_offset as i64),
+            rules,
+            era.format()
+        ));
+        // The next era starts at a local time of this one
+        let end = segments.get(i + 1).and_then(|s| s.start);
+        let last = era.periods.end.checked_sub(1).map(|p| &periods[p]);
+        if let (Some(end), Some(last)) = (end, last) {
+            zic.push_str(&format!("\t{}", until(end + last.utc_offset as i64)?));
+        }
+        zic.push('\n');
+    }
+    Ok(zic)
+}
+
+// RULES field of a Zone line for an era
+fn zone_rules(segment: &Segment) -> Rules {
+    match &segment.dst[..] {
+        [] if !segment.footer => Rules::None,
+        [(save, _)] if segment.std_abbr.is_none() && !segment.footer => Rules::Save(*save),
+        _ => Rules::Named,
+    }
+}
+
+// Folds the occurrences of each rule over consecutive years
+fn fold(mut occurrences: Vec<Rule>) -> Vec<Rule> {
+    occurrences.sort_by(|a, b| {
+        (a.month, a.at, a.suffix, a.save, a.isdst, &a.letter, a.from)
+            .cmp(&(b.month, b.at, b.suffix, b.save, b.isdst, &b.letter, b.from))
+    });
+    let mut rules: Vec<Rule> = Vec::new();
+    for occurrence in occurrences {
+        if let Some(rule) = rules.last_mut() {
+            let days: Vec<Day> = rule
+                .days
+                .iter()
+                .filter(|d| occurrence.days.contains(d))
+                .copied()
+                .collect();
+            let following = rule
+                .to
+                .zip(occurrence.from)
+                .is_some_and(|(to, y)| to + 1 == y);
+            if rule.recurs(&occurrence) && following && !days.is_empty() {
+                rule.to = occurrence.to;
+                rule.days = days;
+                continue;
+            }
+        }
+        rules.push(occurrence);
+    }
+    rules
+}
+
+// DST start and end rules of the footer, from a year
+fn footer_rules(footer: &PosixTz, from: Option<i32>, format: &Format) -> Vec<Rule> {
+    let Some(dst) = &footer.dst else {
+        return Vec::new();
+    };
+    [
+        (
+            dst.start,
+            dst.start_time,
+            dst.utoff - footer.std_utoff,
+            true,
+            &dst.abbr,
+        ),
+        (dst.end, dst.end_time, 0, false, &footer.std_abbr),
+    ]
+    .into_iter()
+    .map(|(date, time, save, isdst, abbreviation)| {
+        let (month, day) = match date {
+            PosixDate::MonthWeekDay {
+                month,
+                week: 5,
+                weekday,
+            } => (month as u32, Day::Last(weekday as u32)),
+            PosixDate::MonthWeekDay {
+                month,
+                week,
+                weekday,
+            } => (
+                month as u32,
+                Day::OnOrAfter(weekday as u32, 1 + 7 * (week as u32 - 1)),
+            ),
+            // February 29th is never counted: the month and day are the ones of a common year
+            PosixDate::Julian(n) => common_year_date(n as u32),
+            // Approximated by the day of a common year
+            PosixDate::ZeroBased(n) => common_year_date(n as u32 + 1),
+        };
+        Rule {
+            from,
+            to: None,
+            month,
+            days: Vec::from([day]),
+            at: time as i64,
+            suffix: "",
+            save,
+            isdst,
+            letter: format.letter(abbreviation),
+        }
+    })
+    .collect()
+}
+
+fn common_year_date(ordinal: u32) -> (u32, Day) {
+    match NaiveDate::from_yo_opt(1970, ordinal) {
+        Some(date) => (date.month(), Day::Fixed(date.day())),
+        None => (12, Day::Fixed(31)),
+    }
+}
+
+// Rule names are the last component of the zone's name (ie. Paris), which must not start like a number
+fn rule_name(zone: &str) -> String {
+    let name: String = zone
+        .rsplit('/')
+        .next()
+        .unwrap_or_default()
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    match name.chars().next() {
+        Some(c) if c.is_ascii_alphabetic() || c == '_' => name,
+        _ => format!("R{}", name),
+    }
+}
+
+// [-]h:mm, followed by the seconds if any
+fn hms(seconds: i64) -> String {
+    let sign = if seconds < 0 { "-" } else { "" };
+    let s = seconds.abs();
+    match s % 60 {
+        0 => format!("{}{}:{:02}", sign, s / 3600, s / 60 % 60),
+        sec => format!("{}{}:{:02}:{:02}", sign, s / 3600, s / 60 % 60, sec),
+    }
+}
+
+// SAVE field: 0 for standard time, 0d for a DST without save amount
+fn save(save: isize, isdst: bool) -> String {
+    match (save, isdst) {
+        (0, false) => String::from("0"),
+        (0, true) => String::from("0d"),
+        (save, _) => hms(save as i64),
+    }
+}
+
+// UNTIL field, its trailing components being omitted when they are the first ones (ie. 1891 Mar 16)
+fn until(local: i64) -> Result<String, TzError> {
+    let local: NaiveDateTime = DateTime::<Utc>::from_timestamp(local, 0)
+        .ok_or(TzError::NoData)?
+        .naive_utc();
+    let time = local.num_seconds_from_midnight();
+    let mut until = local.year().to_string();
+    if (local.month(), local.day(), time) != (1, 1, 0) {
+        until.push_str(&format!(" {}", MONTHS[local.month0() as usize]));
+    }
+    if (local.day(), time) != (1, 0) {
+        until.push_str(&format!(" {}", local.day()));
+    }
+    if time != 0 {
+        until.push_str(&format!(" {}", hms(time as i64)));
+    }
+    Ok(until)
+}